@@ -0,0 +1,247 @@
+//! Benchmarks of the ray casting and rendering code, run by `cargo bench`. All frames are rendered into a headless frame buffer, so no
+//! window is needed. Pass `--baseline <path>` (e.g. `cargo bench -- --baseline benchmark.csv`) to compare the results with a previous
+//! run and save them for the next one.
+
+extern crate raycaster;
+
+use raycaster::game::Game;
+use raycaster::game::map::Map;
+use raycaster::game::player::Player;
+use raycaster::renderer::Renderer;
+use raycaster::renderer::profiler::Profiler;
+use raycaster::renderer::ray::Ray;
+use raycaster::utils::frame_buffer::FrameBuffer;
+use raycaster::utils::geometry::Point;
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::hint::black_box;
+use std::io;
+use std::process::exit;
+use std::time::Duration;
+use std::time::Instant;
+
+fn main() {
+	let arguments: Vec<String> = env::args().collect();
+	let baseline_path = arguments.iter().position(|argument| argument == "--baseline").and_then(|index| arguments.get(index + 1));
+
+	if let Err(error) = run_all(baseline_path.map(|path| path.as_str())) {
+		eprintln!("Could not run the benchmarks: {}", error);
+		exit(1);
+	}
+}
+
+/// Measures how long a function takes, similar to Criterion: the function is warmed up, then run for a number of samples of many
+/// iterations each. The statistics of all samples are printed and compared to the results of a previous run.
+struct Benchmark {
+	/// The time spent on running a function before measuring it
+	warm_up_time: Duration,
+
+	/// The approximate time spent on measuring a function
+	measurement_time: Duration,
+
+	/// The number of samples taken of each function
+	sample_count: usize,
+
+	/// Changes of the mean below this relative threshold are considered noise
+	noise_threshold: f64,
+
+	/// The mean time per iteration (in nanoseconds) of each function measured by a previous run
+	baseline: HashMap<String, f64>,
+
+	/// The results of all functions measured so far
+	results: Vec<BenchmarkResult>
+}
+
+/// The statistics of a measured function. All times are given in nanoseconds per iteration.
+struct BenchmarkResult {
+	name: String,
+	mean: f64,
+	median: f64,
+	standard_deviation: f64,
+	minimum: f64,
+	maximum: f64
+}
+
+impl Benchmark {
+	/// Creates a benchmark with 50 samples taken over 3 seconds per function.
+	fn new() -> Benchmark {
+		Benchmark {
+			warm_up_time: Duration::from_secs(1), measurement_time: Duration::from_secs(3), sample_count: 50, noise_threshold: 0.05,
+			baseline: HashMap::new(), results: Vec::new()
+		}
+	}
+
+	/// Measures a function and prints its statistics.
+	///
+	/// # Parameters:
+	///		- name:			The name of the function shown in the results
+	///		- function:		The function to be measured. Its result is passed through `black_box`, so it is not optimized away.
+	fn run<T, F>(&mut self, name: &str, mut function: F) where F: FnMut() -> T {
+		// Warm up caches and estimate the time of a single iteration
+		let warm_up_start = Instant::now();
+		let mut warm_up_iterations = 0u64;
+		while warm_up_start.elapsed() < self.warm_up_time {
+			black_box(function());
+			warm_up_iterations += 1;
+		}
+		let estimated_time = warm_up_start.elapsed().as_secs_f64() / warm_up_iterations as f64;
+
+		// Each sample runs as many iterations as fit into its share of the measurement time
+		let sample_time = self.measurement_time.as_secs_f64() / self.sample_count as f64;
+		let iterations = ((sample_time / estimated_time) as u64).max(1);
+
+		let mut samples: Vec<f64> = (0..self.sample_count).map(|_| {
+			let start = Instant::now();
+			for _ in 0..iterations {
+				black_box(function());
+			}
+			start.elapsed().as_secs_f64() * 1e9 / iterations as f64
+		}).collect();
+		samples.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+		let result = BenchmarkResult::new(name, &samples);
+		println!("{}", self.describe(&result));
+		self.results.push(result);
+	}
+
+	/// Loads the results of a previous run that the following measurements are compared to. A missing file is not an error, since
+	/// there is no baseline on the first run.
+	fn load_baseline(&mut self, path: &str) -> io::Result<()> {
+		let contents = match fs::read_to_string(path) {
+			Ok(contents) => contents,
+			Err(ref error) if error.kind() == io::ErrorKind::NotFound => return Ok(()),
+			Err(error) => return Err(error)
+		};
+
+		for line in contents.lines().skip(1) {
+			let fields: Vec<&str> = line.split(',').collect();
+			if let (Some(name), Some(Ok(mean))) = (fields.first(), fields.get(1).map(|mean| mean.parse::<f64>())) {
+				self.baseline.insert(name.to_string(), mean);
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Saves the results of all measured functions as CSV, so they can be used as baseline for a later run.
+	fn save_results(&self, path: &str) -> io::Result<()> {
+		let mut csv = String::from("name,mean,median,standard_deviation,minimum,maximum\n");
+		for result in self.results.iter() {
+			csv += &format!(
+				"{},{:.1},{:.1},{:.1},{:.1},{:.1}\n",
+				result.name, result.mean, result.median, result.standard_deviation, result.minimum, result.maximum
+			);
+		}
+
+		fs::write(path, csv)
+	}
+
+	/// Describes a result and its change compared to the baseline.
+	fn describe(&self, result: &BenchmarkResult) -> String {
+		let mut description = format!(
+			"{:<40} time: [{} {} {}]  mean: {} ± {}",
+			result.name, format_time(result.minimum), format_time(result.median), format_time(result.maximum),
+			format_time(result.mean), format_time(result.standard_deviation)
+		);
+
+		if let Some(&baseline) = self.baseline.get(&result.name) {
+			let change = (result.mean - baseline) / baseline;
+			let verdict = if change.abs() <= self.noise_threshold { "within noise" } else if change > 0.0 { "REGRESSED" } else { "improved" };
+			description += &format!("  change: {:+.2} % ({})", change * 100.0, verdict);
+		}
+
+		description
+	}
+}
+
+impl BenchmarkResult {
+	/// Computes the statistics of sorted samples.
+	fn new(name: &str, samples: &[f64]) -> BenchmarkResult {
+		let count = samples.len() as f64;
+		let mean = samples.iter().sum::<f64>() / count;
+		let variance = samples.iter().map(|sample| (sample - mean).powi(2)).sum::<f64>() / (count - 1.0).max(1.0);
+		// `usize::is_multiple_of` would raise the minimum supported Rust version to 1.87
+		#[allow(clippy::manual_is_multiple_of)]
+		let median = if samples.len() % 2 == 0 {
+			(samples[samples.len() / 2 - 1] + samples[samples.len() / 2]) / 2.0
+		} else {
+			samples[samples.len() / 2]
+		};
+
+		BenchmarkResult {
+			name: name.to_string(), mean, median, standard_deviation: variance.sqrt(),
+			minimum: samples[0], maximum: samples[samples.len() - 1]
+		}
+	}
+}
+
+/// Formats a time given in nanoseconds with a fitting unit.
+fn format_time(nanoseconds: f64) -> String {
+	if nanoseconds < 1e3 {
+		format!("{:.2} ns", nanoseconds)
+	} else if nanoseconds < 1e6 {
+		format!("{:.2} µs", nanoseconds / 1e3)
+	} else if nanoseconds < 1e9 {
+		format!("{:.2} ms", nanoseconds / 1e6)
+	} else {
+		format!("{:.2} s", nanoseconds / 1e9)
+	}
+}
+
+/// Runs the benchmarks of the ray casting and rendering code. All frames are rendered into a headless frame buffer, so no window is needed.
+///
+/// # Parameters:
+///		- baseline_path:	A CSV file with the results of a previous run. The results are compared to it and saved to it afterwards.
+fn run_all(baseline_path: Option<&str>) -> io::Result<()> {
+	let mut benchmark = Benchmark::new();
+	if let Some(path) = baseline_path {
+		benchmark.load_baseline(path)?;
+	}
+
+	// The DDA step alone: a ray crossing 1000 grid lines
+	benchmark.run("ray/grow x1000", || {
+		let mut ray = Ray::new(Point {x: 0.5, y: 0.5}, 0.7);
+		for _ in 0..1000 {
+			ray.grow();
+		}
+		ray.length
+	});
+
+	// The worst case of casting a ray: a large open map the ray crosses diagonally before hitting the opposite wall ...
+	let size = 256;
+	let diagonal = (size as f64 - 1.5).atan2(size as f64 - 1.5);
+	let walled = benchmark_renderer(open_map(size, true), Point {x: 1.5, y: 1.5}, diagonal);
+	benchmark.run("cast_ray/diagonal 256x256", || walled.cast_ray_for_benchmark(0.0));
+
+	// ... and a ray leaving the map without hitting a wall, which is only stopped at the map's maximum distance
+	let unwalled = benchmark_renderer(open_map(size, false), Point {x: 1.5, y: 1.5}, diagonal);
+	benchmark.run("cast_ray/escaping 256x256", || unwalled.cast_ray_for_benchmark(0.0));
+
+	// Complete frames of the demo map
+	let renderer = benchmark_renderer(Map::new(include_str!("../assets/map.txt")), Point {x: 4.5, y: 5.5}, 0.0);
+	for &(width, height) in [(320, 200), (640, 400), (1280, 800), (1920, 1080)].iter() {
+		let mut frame_buffer = FrameBuffer::new(width, height);
+		benchmark.run(&format!("render/{}x{}", width, height), || frame_buffer.draw_canvas(|canvas| renderer.render(canvas)));
+	}
+
+	if let Some(path) = baseline_path {
+		benchmark.save_results(path)?;
+	}
+
+	Ok(())
+}
+
+/// Creates a square map of empty tiles, optionally surrounded by walls.
+fn open_map(size: usize, walled: bool) -> Map {
+	let wall_row: String = "R".repeat(size);
+	let open_row: String = if walled { format!("R{}R", " ".repeat(size - 2)) } else { " ".repeat(size) };
+
+	let rows: Vec<&str> = (0..size).map(|y| if walled && (y == 0 || y == size - 1) { wall_row.as_str() } else { open_row.as_str() }).collect();
+	Map::new(&rows.join("\n"))
+}
+
+/// Creates a renderer with the default settings of the demo and a player at the given position.
+fn benchmark_renderer(map: Map, position: Point, direction: f64) -> Renderer {
+	Renderer::new(Game::new(map, Player {position, direction}), 67.0, 100.0, 0.25, Profiler::new(false))
+}
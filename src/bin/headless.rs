@@ -0,0 +1,67 @@
+extern crate raycaster;
+
+use raycaster::game::Game;
+use raycaster::game::map::Map;
+use raycaster::game::player::Player;
+use raycaster::renderer::Renderer;
+use raycaster::renderer::profiler::Profiler;
+use raycaster::utils::frame_buffer::FrameBuffer;
+use raycaster::utils::geometry::Point;
+use std::env;
+use std::fs;
+use std::fs::File;
+use std::io::BufWriter;
+use std::process::exit;
+
+/// Renders a single frame without a window and writes it to a PPM image. Works without SDL.
+///
+/// Usage: raycaster-headless [--output <path>] [--size <width>x<height>] [--position <x>,<y>] [--direction <degrees>] [--map <path>]
+fn main() {
+	let arguments: Vec<String> = env::args().collect();
+	let output_path = option_value(&arguments, "--output").unwrap_or_else(|| "frame.ppm".to_string());
+	let (width, height) = parse_pair(&arguments, "--size", 'x').unwrap_or((800, 600));
+	let (x, y) = parse_pair(&arguments, "--position", ',').unwrap_or((4.5, 5.5));
+	let direction: f64 = option_value(&arguments, "--direction").map_or(Ok(0.0), |degrees| degrees.parse()).unwrap_or_else(|_| fail("Invalid direction"));
+
+	let map = match option_value(&arguments, "--map") {
+		Some(path) => {
+			let map_string = fs::read_to_string(&path).unwrap_or_else(|error| fail(&format!("Could not read the map {}: {}", path, error)));
+			Map::parse(&map_string).unwrap_or_else(|error| fail(&format!("Invalid map {}: {}", path, error)))
+		},
+		None => Map::new(include_str!("../../assets/map.txt"))
+	};
+
+	let mut game = Game::new(map, Player {position: Point {x, y}, direction: direction.to_radians()});
+	game.update_lights(0.0);
+	let renderer = Renderer::new(game, 67.0, 100.0, 0.25, Profiler::new(false));
+
+	let mut frame_buffer = FrameBuffer::new(width, height);
+	frame_buffer.draw_canvas(|canvas| renderer.render(canvas));
+
+	let result = File::create(&output_path).and_then(|file| frame_buffer.write_ppm(&mut BufWriter::new(file)));
+	if let Err(error) = result {
+		fail(&format!("Could not write the frame to {}: {}", output_path, error));
+	}
+}
+
+/// Returns the value following an option on the command line.
+fn option_value(arguments: &[String], option: &str) -> Option<String> {
+	arguments.iter().position(|argument| argument == option).and_then(|index| arguments.get(index + 1)).cloned()
+}
+
+/// Parses the two values of an option separated by a character (e.g. "640x400").
+fn parse_pair<T: std::str::FromStr>(arguments: &[String], option: &str, separator: char) -> Option<(T, T)> {
+	let value = option_value(arguments, option)?;
+	let mut parts = value.splitn(2, separator).map(|part| part.trim().parse::<T>());
+
+	match (parts.next(), parts.next()) {
+		(Some(Ok(first)), Some(Ok(second))) => Some((first, second)),
+		_ => fail(&format!("Invalid value for {}: {}", option, value))
+	}
+}
+
+/// Prints an error message and exits.
+fn fail(message: &str) -> ! {
+	eprintln!("{}", message);
+	exit(1);
+}
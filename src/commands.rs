@@ -0,0 +1,214 @@
+#[cfg(feature = "debug")]
+use raycaster::game::map::TilePosition;
+use raycaster::demo::load_game;
+use raycaster::demo::variables::VARIABLES;
+#[cfg(feature = "debug")]
+use raycaster::demo::variables::parse_number;
+#[cfg(feature = "debug")]
+use raycaster::demo::variables::switch_name;
+use raycaster::renderer::Renderer;
+use raycaster::utils::frame_buffer::FrameBuffer;
+#[cfg(feature = "debug")]
+use raycaster::utils::geometry::Point;
+use raycaster::window::Window;
+use std::fs;
+use std::fs::File;
+use std::path::Path;
+use quit;
+use State;
+
+/// A console command, e.g. `teleport 4.5 5.5`.
+struct Command {
+	name: &'static str,
+
+	/// The arguments of the command, as shown by `help`
+	arguments: &'static str,
+	description: &'static str,
+
+	/// Runs the command with the given arguments. Returns an error message if the command failed.
+	run: fn(&[&str], &mut Window, &mut Renderer, &mut State) -> Result<(), String>
+}
+
+/// The commands of the console. The commands for moving freely through the map are only available with the `debug` feature.
+const COMMANDS: &[Command] = &[
+	Command {name: "help", arguments: "", description: "Lists all commands and variables", run: help},
+	Command {name: "clear", arguments: "", description: "Clears the console", run: |_, _, renderer, _| { renderer.console.clear(); Ok(()) }},
+	Command {name: "exec", arguments: "<file>", description: "Runs the commands of a script file, one per line", run: exec},
+	Command {name: "map", arguments: "<file>", description: "Loads a map file (\"default\" for the built-in map)", run: map},
+	#[cfg(feature = "debug")]
+	Command {name: "noclip", arguments: "", description: "Lets the player move through walls", run: noclip},
+	#[cfg(feature = "debug")]
+	Command {name: "fly", arguments: "", description: "Lets the player fly up (E) and down (Q)", run: fly},
+	#[cfg(feature = "debug")]
+	Command {name: "teleport", arguments: "<x> <y>", description: "Places the player at a position of the map", run: teleport},
+	#[cfg(feature = "debug")]
+	Command {name: "teleport_tile", arguments: "<column> <row>", description: "Places the player at the center of a tile", run: teleport_tile},
+	Command {name: "screenshot", arguments: "[file]", description: "Saves the current frame as PPM image", run: screenshot},
+	Command {name: "quit", arguments: "", description: "Quits the demo", run: |_, _, renderer, state| quit(renderer, &state.frame_timings_path)}
+];
+
+/// The names of all commands and variables, e.g. for completing the input of the console
+pub fn names() -> Vec<&'static str> {
+	COMMANDS.iter().map(|command| command.name).chain(VARIABLES.iter().map(|variable| variable.name)).collect()
+}
+
+/// Executes a line entered into the console or read from a script. Errors are printed to the console.
+///
+/// # Parameters:
+///		- line:			The command or variable and its arguments, separated by spaces
+///		- window:		The window showing the console
+///		- renderer:		The renderer whose console prints the output
+///		- state:		The state of the demo
+pub fn execute(line: &str, window: &mut Window, renderer: &mut Renderer, state: &mut State) {
+	let words: Vec<&str> = line.split_whitespace().collect();
+	let result = match words.split_first() {
+		Some((name, arguments)) => run(name, arguments, window, renderer, state),
+		None => Ok(())
+	};
+
+	if let Err(error) = result {
+		renderer.console.print(&error);
+	}
+}
+
+/// Executes all lines of a script file. Empty lines and comments starting with `#` or `//` are skipped. Returns an error if the file
+/// can't be read, errors of single lines are printed to the console.
+///
+/// # Parameters:
+///		- path:			The path of the script file
+///		- window:		The window showing the console
+///		- renderer:		The renderer whose console prints the output
+///		- state:		The state of the demo
+pub fn exec_script(path: &str, window: &mut Window, renderer: &mut Renderer, state: &mut State) -> Result<(), String> {
+	let script = fs::read_to_string(path).map_err(|error| format!("Could not read the script {}: {}", path, error))?;
+
+	for line in script.lines().map(|line| line.trim()) {
+		if !line.is_empty() && !line.starts_with('#') && !line.starts_with("//") {
+			execute(line, window, renderer, state);
+		}
+	}
+
+	Ok(())
+}
+
+/// Runs a command or shows or changes a variable.
+fn run(name: &str, arguments: &[&str], window: &mut Window, renderer: &mut Renderer, state: &mut State) -> Result<(), String> {
+	if let Some(command) = COMMANDS.iter().find(|command| command.name == name) {
+		return (command.run)(arguments, window, renderer, state);
+	}
+
+	let variable = VARIABLES.iter().find(|variable| variable.name == name).ok_or_else(|| format!("Unknown command: {}", name))?;
+	match *arguments {
+		[] => { renderer.console.print(&format!("{} = {}", variable.name, (variable.get)(renderer))); },
+		[value] => { (variable.set)(renderer, value).ok_or_else(|| format!("Invalid value for {}: {}", variable.name, value))?; },
+		_ => { return Err(format!("Usage: {} [value]", variable.name)); }
+	}
+
+	Ok(())
+}
+
+/// The message shown if a command is called with wrong arguments
+fn usage(name: &str) -> String {
+	let arguments = COMMANDS.iter().find(|command| command.name == name).map_or("", |command| command.arguments);
+	format!("Usage: {} {}", name, arguments)
+}
+
+/// Lists all commands and variables.
+fn help(_: &[&str], _: &mut Window, renderer: &mut Renderer, _: &mut State) -> Result<(), String> {
+	for command in COMMANDS.iter() {
+		let text = format!("{} {}", command.name, command.arguments);
+		renderer.console.print(&format!("{} - {}", text.trim(), command.description));
+	}
+	for variable in VARIABLES.iter() {
+		let text = format!("{} = {} - {}", variable.name, (variable.get)(renderer), variable.description);
+		renderer.console.print(&text);
+	}
+
+	Ok(())
+}
+
+/// Runs a script file.
+fn exec(arguments: &[&str], window: &mut Window, renderer: &mut Renderer, state: &mut State) -> Result<(), String> {
+	match *arguments {
+		[path] => exec_script(path, window, renderer, state),
+		_ => Err(usage("exec"))
+	}
+}
+
+/// Loads a map and closes the menu, so the map can be played right away. The map is not stored in the settings.
+fn map(arguments: &[&str], _: &mut Window, renderer: &mut Renderer, _: &mut State) -> Result<(), String> {
+	let path = match *arguments {
+		["default"] => None,
+		[path] => Some(path.to_string()),
+		_ => { return Err(usage("map")); }
+	};
+
+	renderer.game = load_game(&path)?;
+	renderer.menu.visible = false;
+	renderer.console.print(&format!("Loaded the map {}", path.as_ref().map_or("default", |path| path.as_str())));
+	Ok(())
+}
+
+/// Toggles whether the player can move through walls.
+#[cfg(feature = "debug")]
+fn noclip(_: &[&str], _: &mut Window, renderer: &mut Renderer, _: &mut State) -> Result<(), String> {
+	let debug_movement = &mut renderer.game.debug_movement;
+	debug_movement.noclip = !debug_movement.noclip;
+	renderer.console.print(&format!("noclip {}", switch_name(debug_movement.noclip)));
+	Ok(())
+}
+
+/// Toggles whether the player can fly up and down. The player returns to the normal eye height when landing.
+#[cfg(feature = "debug")]
+fn fly(_: &[&str], _: &mut Window, renderer: &mut Renderer, _: &mut State) -> Result<(), String> {
+	let fly = !renderer.game.debug_movement.fly;
+	renderer.game.set_flying(fly);
+	renderer.console.print(&format!("fly {}", switch_name(fly)));
+	Ok(())
+}
+
+/// Places the player at a position of the map.
+#[cfg(feature = "debug")]
+fn teleport(arguments: &[&str], _: &mut Window, renderer: &mut Renderer, _: &mut State) -> Result<(), String> {
+	let position = match *arguments {
+		[x, y] => parse_number(x, -1e6, 1e6).and_then(|x| parse_number(y, -1e6, 1e6).map(|y| Point {x, y})),
+		_ => None
+	};
+
+	renderer.game.teleport_player(position.ok_or_else(|| usage("teleport"))?)
+}
+
+/// Places the player at the center of a tile.
+#[cfg(feature = "debug")]
+fn teleport_tile(arguments: &[&str], _: &mut Window, renderer: &mut Renderer, _: &mut State) -> Result<(), String> {
+	let tile = match *arguments {
+		[x, y] => x.parse().ok().and_then(|x| y.parse().ok().map(|y| TilePosition {x, y})),
+		_ => None
+	};
+
+	renderer.game.teleport_player_to_tile(&tile.ok_or_else(|| usage("teleport_tile"))?)
+}
+
+/// Renders the current frame without the console and saves it as PPM image. Without a path, the image is written to the next unused
+/// `screenshot-<number>.ppm` file of the working directory.
+fn screenshot(arguments: &[&str], window: &mut Window, renderer: &mut Renderer, _: &mut State) -> Result<(), String> {
+	let path = match *arguments {
+		[path] => path.to_string(),
+		[] => (1..).map(|number| format!("screenshot-{:03}.ppm", number)).find(|path| !Path::new(path).exists()).unwrap(),
+		_ => { return Err(usage("screenshot")); }
+	};
+
+	let (width, height) = window.view.frame_size();
+	let mut frame_buffer = FrameBuffer::new(width as usize, height as usize);
+	let console_visible = renderer.console.visible;
+	renderer.console.visible = false;
+	frame_buffer.draw_canvas(|canvas| renderer.render(canvas));
+	renderer.console.visible = console_visible;
+
+	File::create(&path)
+		.and_then(|mut file| frame_buffer.write_ppm(&mut file))
+		.map_err(|error| format!("Could not write the screenshot {}: {}", path, error))?;
+
+	renderer.console.print(&format!("Saved the screenshot {}", path));
+	Ok(())
+}
@@ -0,0 +1,417 @@
+use demo::settings::Action;
+use demo::settings::Settings;
+use renderer::Renderer;
+use renderer::menu::MenuItem;
+use window::ViewSettings;
+use window::event::Keycode;
+use window::render_scale::Filtering;
+use window::render_scale::Upscaling;
+use std::fs;
+use std::path::Path;
+
+/// The screens of the menu.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Screen {
+	/// Shown at startup
+	Title,
+
+	/// Shown while the game is paused
+	Pause,
+
+	/// Leads to the pages of the settings
+	Settings,
+
+	/// The field of view, lighting, render scale and fullscreen mode
+	Video,
+
+	/// The mouse sensitivity and the key bindings
+	Controls,
+
+	/// The maps that can be played
+	Maps
+}
+
+/// The input used for navigating the menu. Keyboard, mouse and controller input is mapped to it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MenuInput {
+	/// Select the previous/next item
+	Previous, Next,
+
+	/// Decrease/increase the value of the selected setting
+	Decrease, Increase,
+
+	/// Choose the selected item
+	Choose,
+
+	/// Return to the previous screen
+	Back
+}
+
+/// What the application has to do after an input was handled by the menu.
+#[derive(Clone, Debug, PartialEq)]
+pub enum MenuAction {
+	/// Nothing, the menu stays open
+	None,
+
+	/// Close the menu and continue the game
+	Resume,
+
+	/// Quit the application
+	Quit,
+
+	/// Start a new game on a map (`None` for the built-in map) and close the menu
+	LoadMap(Option<String>)
+}
+
+/// The items of the screens.
+#[derive(Clone, Debug, PartialEq)]
+enum Entry {
+	Start, Resume, Quit, Back,
+	Open(Screen),
+	FieldOfView, IlluminationRadius, MinimumLight, Resolution, Upscaling, Filtering, Fullscreen,
+	MouseSensitivity, Binding(Action),
+	Map(Option<String>)
+}
+
+/// Keeps track of the screens of the menu shown by the renderer. The items are rebuilt whenever a setting changes, so they always show
+/// the current values.
+pub struct Menus {
+	/// The opened screens and the items selected on them. The current screen is the last one.
+	screens: Vec<(Screen, usize)>,
+
+	/// The action waiting for a key to be bound to it
+	rebinding: Option<Action>
+}
+
+impl Menus {
+	/// Creates the menus. No screen is open.
+	pub fn new() -> Menus {
+		Menus {screens: Vec::new(), rebinding: None}
+	}
+
+	/// Shows a screen, replacing all opened screens.
+	pub fn open(&mut self, screen: Screen, view: &dyn ViewSettings, renderer: &mut Renderer, settings: &Settings) {
+		self.screens.clear();
+		self.rebinding = None;
+		self.push(screen, view, renderer, settings);
+	}
+
+	/// Whether the menu waits for a key to be bound to an action. All keys should be passed to `bind_key` then.
+	pub fn is_rebinding(&self) -> bool {
+		self.rebinding.is_some()
+	}
+
+	/// Binds a pressed key to the action waiting for it. Escape cancels, reserved keys keep the menu waiting for another key.
+	pub fn bind_key(&mut self, keycode: Keycode, view: &dyn ViewSettings, renderer: &mut Renderer, settings: &mut Settings) {
+		renderer.menu.footer.clear();
+
+		if let Some(action) = self.rebinding.take() {
+			if keycode != Keycode::Escape {
+				match settings.set_binding(action, keycode) {
+					Ok(()) => Menus::save(view, renderer, settings),
+					Err(error) => {
+						self.rebinding = Some(action);
+						renderer.menu.footer = format!("{}, press another key (Escape cancels)", error);
+					}
+				}
+			}
+		}
+
+		self.refresh(view, renderer, settings);
+	}
+
+	/// Handles an input for the current screen. Returns what the application has to do.
+	pub fn handle(&mut self, input: MenuInput, view: &mut dyn ViewSettings, renderer: &mut Renderer, settings: &mut Settings) -> MenuAction {
+		let screen = match self.screens.last() {
+			Some(&(screen, _)) => screen,
+			None => return MenuAction::None
+		};
+		let entry = self.entries(screen, settings).get(renderer.menu.selected).cloned();
+
+		match input {
+			MenuInput::Previous => { renderer.menu.select_previous(); },
+			MenuInput::Next => { renderer.menu.select_next(); },
+			MenuInput::Back => return self.back(view, renderer, settings),
+
+			// Only the values of settings are decreased/increased, other items have to be chosen
+			MenuInput::Decrease | MenuInput::Increase => {
+				let step = if input == MenuInput::Decrease { -1.0 } else { 1.0 };
+
+				if let Some(entry) = entry {
+					self.change(&entry, step, view, renderer, settings);
+				}
+			},
+
+			MenuInput::Choose => {
+				match entry {
+					Some(Entry::Start) | Some(Entry::Resume) => return MenuAction::Resume,
+					Some(Entry::Quit) => return MenuAction::Quit,
+					Some(Entry::Back) => return self.back(view, renderer, settings),
+					Some(Entry::Open(screen)) => { self.push(screen, view, renderer, settings); },
+					Some(Entry::Map(map)) => return MenuAction::LoadMap(map),
+
+					Some(Entry::Binding(action)) => {
+						self.rebinding = Some(action);
+						renderer.menu.footer = format!("Press a key for \"{}\" (Escape cancels)", action.label());
+					},
+
+					Some(entry) => { self.change(&entry, 1.0, view, renderer, settings); },
+					None => {}
+				}
+			}
+		}
+
+		MenuAction::None
+	}
+
+	/// Changes a setting by one step and saves the settings. Items that are not settings are left alone.
+	///
+	/// # Parameters:
+	///		- entry:		The item of the setting
+	///		- step:			1.0 for increasing the setting, -1.0 for decreasing it
+	///		- view:			The view whose settings may be changed
+	///		- renderer:		The renderer whose settings may be changed
+	///		- settings:		The settings that may be changed
+	fn change(&self, entry: &Entry, step: f64, view: &mut dyn ViewSettings, renderer: &mut Renderer, settings: &mut Settings) {
+		if Menus::adjust(entry, step, view, renderer, settings) {
+			Menus::save(view, renderer, settings);
+			self.refresh(view, renderer, settings);
+		}
+	}
+
+	/// Changes a setting by one step. Returns whether the item is a setting.
+	///
+	/// # Parameters:
+	///		- entry:		The item of the setting
+	///		- step:			1.0 for increasing the setting, -1.0 for decreasing it
+	///		- view:			The view whose settings may be changed
+	///		- renderer:		The renderer whose settings may be changed
+	///		- settings:		The settings that may be changed
+	fn adjust(entry: &Entry, step: f64, view: &mut dyn ViewSettings, renderer: &mut Renderer, settings: &mut Settings) -> bool {
+		// Values are rounded, so repeated steps don't accumulate rounding errors
+		let round = |value: f64| (value * 100.0).round() / 100.0;
+		let mut render_scale = view.render_scale().clone();
+
+		match *entry {
+			Entry::FieldOfView => { renderer.field_of_view = (renderer.field_of_view + 5.0 * step).clamp(30.0, 150.0); },
+			Entry::IlluminationRadius => { renderer.illumination_radius = (renderer.illumination_radius + 5.0 * step).clamp(5.0, 200.0); },
+			Entry::MinimumLight => { renderer.minimum_light = round(renderer.minimum_light + 0.05 * step).clamp(0.0, 1.0); },
+			Entry::MouseSensitivity => { settings.mouse_sensitivity = round(settings.mouse_sensitivity + 0.1 * step).clamp(0.1, 5.0); },
+
+			Entry::Resolution => {
+				render_scale.resolution = if step < 0.0 { render_scale.resolution.previous_preset() } else { render_scale.resolution.next_preset() };
+				view.set_render_scale(render_scale);
+			},
+			Entry::Upscaling => {
+				render_scale.upscaling = if render_scale.upscaling == Upscaling::Integer { Upscaling::Fractional } else { Upscaling::Integer };
+				view.set_render_scale(render_scale);
+			},
+			Entry::Filtering => {
+				render_scale.filtering = if render_scale.filtering == Filtering::Linear { Filtering::Nearest } else { Filtering::Linear };
+				view.set_render_scale(render_scale);
+			},
+			Entry::Fullscreen => {
+				let fullscreen = view.fullscreen().next();
+				if let Err(error) = view.set_fullscreen(fullscreen) {
+					renderer.hud.show_message(&error);
+				}
+			},
+
+			_ => { return false; }
+		}
+
+		true
+	}
+
+	/// Writes the settings to the configuration file. Errors are shown on the HUD, the settings stay applied anyway.
+	fn save(view: &dyn ViewSettings, renderer: &mut Renderer, settings: &mut Settings) {
+		if let Err(error) = settings.save(view, renderer) {
+			renderer.hud.show_message(&error);
+		}
+	}
+
+	/// Returns to the previous screen. The first screen is left by resuming the game, except for the title screen.
+	fn back(&mut self, view: &dyn ViewSettings, renderer: &mut Renderer, settings: &Settings) -> MenuAction {
+		if self.screens.len() <= 1 {
+			return if self.screens.last().map(|&(screen, _)| screen) == Some(Screen::Title) { MenuAction::None } else { MenuAction::Resume };
+		}
+
+		self.screens.pop();
+		let selected = self.screens.last().map_or(0, |&(_, selected)| selected);
+		self.show_current(selected, view, renderer, settings);
+		MenuAction::None
+	}
+
+	/// Opens a screen on top of the current one. The selection of the current screen is restored when returning to it.
+	fn push(&mut self, screen: Screen, view: &dyn ViewSettings, renderer: &mut Renderer, settings: &Settings) {
+		if let Some(current) = self.screens.last_mut() {
+			current.1 = renderer.menu.selected;
+		}
+
+		self.screens.push((screen, 0));
+		self.show_current(0, view, renderer, settings);
+	}
+
+	/// Rebuilds the items of the current screen, keeping the selection and the footer.
+	fn refresh(&self, view: &dyn ViewSettings, renderer: &mut Renderer, settings: &Settings) {
+		let selected = renderer.menu.selected;
+		let footer = renderer.menu.footer.clone();
+
+		self.show_current(selected, view, renderer, settings);
+		renderer.menu.footer = footer;
+	}
+
+	/// Shows the items of the current screen.
+	fn show_current(&self, selected: usize, view: &dyn ViewSettings, renderer: &mut Renderer, settings: &Settings) {
+		if let Some(&(screen, _)) = self.screens.last() {
+			let items: Vec<MenuItem> = self.entries(screen, settings).iter().map(|entry| Menus::item(entry, view, renderer, settings)).collect();
+			renderer.menu.show(Menus::title(screen), items);
+			renderer.menu.selected = selected.min(renderer.menu.items.len().saturating_sub(1));
+		}
+	}
+
+	/// The title of a screen
+	fn title(screen: Screen) -> &'static str {
+		match screen {
+			Screen::Title => "Ray Casting Demo",
+			Screen::Pause => "Paused",
+			Screen::Settings => "Settings",
+			Screen::Video => "Video",
+			Screen::Controls => "Controls",
+			Screen::Maps => "Maps"
+		}
+	}
+
+	/// The items of a screen
+	fn entries(&self, screen: Screen, settings: &Settings) -> Vec<Entry> {
+		match screen {
+			Screen::Title => vec![Entry::Start, Entry::Open(Screen::Settings), Entry::Quit],
+			Screen::Pause => vec![Entry::Resume, Entry::Open(Screen::Settings), Entry::Quit],
+			Screen::Settings => vec![Entry::Open(Screen::Video), Entry::Open(Screen::Controls), Entry::Open(Screen::Maps), Entry::Back],
+			Screen::Video => vec![
+				Entry::FieldOfView, Entry::IlluminationRadius, Entry::MinimumLight, Entry::Resolution, Entry::Upscaling, Entry::Filtering,
+				Entry::Fullscreen, Entry::Back
+			],
+			Screen::Controls => {
+				let mut entries = vec![Entry::MouseSensitivity];
+				entries.extend(Action::ALL.iter().map(|action| Entry::Binding(*action)));
+				entries.push(Entry::Back);
+				entries
+			},
+			Screen::Maps => {
+				let mut entries = vec![Entry::Map(None)];
+				entries.extend(Menus::map_paths(&settings.map_directory).into_iter().map(|path| Entry::Map(Some(path))));
+				entries.push(Entry::Back);
+				entries
+			}
+		}
+	}
+
+	/// Creates the menu item of an entry, showing the current value of settings.
+	fn item(entry: &Entry, view: &dyn ViewSettings, renderer: &Renderer, settings: &Settings) -> MenuItem {
+		let render_scale = view.render_scale();
+
+		match *entry {
+			Entry::Start => MenuItem::new("Start"),
+			Entry::Resume => MenuItem::new("Resume"),
+			Entry::Quit => MenuItem::new("Quit"),
+			Entry::Back => MenuItem::new("Back"),
+			Entry::Open(screen) => MenuItem::new(Menus::title(screen)),
+			Entry::FieldOfView => MenuItem::setting("Field of view", &format!("{:.0} deg", renderer.field_of_view)),
+			Entry::IlluminationRadius => MenuItem::setting("Light radius", &format!("{:.0}", renderer.illumination_radius)),
+			Entry::MinimumLight => MenuItem::setting("Ambient light", &format!("{:.0} %", renderer.minimum_light * 100.0)),
+			Entry::Resolution => MenuItem::setting("Resolution", &render_scale.resolution.to_string()),
+			Entry::Upscaling => MenuItem::setting("Upscaling", &render_scale.upscaling.to_string()),
+			Entry::Filtering => MenuItem::setting("Filtering", &render_scale.filtering.to_string()),
+			Entry::Fullscreen => MenuItem::setting("Fullscreen", &view.fullscreen().to_string()),
+			Entry::MouseSensitivity => MenuItem::setting("Mouse sensitivity", &format!("{:.1}", settings.mouse_sensitivity)),
+			Entry::Binding(action) => MenuItem::setting(action.label(), &settings.binding(action).to_string()),
+			Entry::Map(ref map) => {
+				let name = map.as_ref().and_then(|path| Path::new(path).file_stem()).map_or("Default".to_string(), |name| name.to_string_lossy().into_owned());
+				if *map == settings.map { MenuItem::setting(&name, "current") } else { MenuItem::new(&name) }
+			}
+		}
+	}
+
+	/// Finds the maps (".txt" files) in a directory, sorted by name. A missing directory contains no maps.
+	fn map_paths(directory: &str) -> Vec<String> {
+		let mut paths: Vec<String> = fs::read_dir(directory).into_iter()
+			.flat_map(|entries| entries.filter_map(|entry| entry.ok()))
+			.map(|entry| entry.path())
+			.filter(|path| path.extension().is_some_and(|extension| extension == "txt"))
+			.map(|path| path.to_string_lossy().into_owned())
+			.collect();
+
+		paths.sort();
+		paths
+	}
+}
+
+impl Default for Menus {
+	fn default() -> Menus {
+		Menus::new()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use game::Game;
+	use game::map::Map;
+	use game::player::Player;
+	use renderer::profiler::Profiler;
+	use utils::config::Config;
+	use utils::geometry::Point;
+	use window::config::Fullscreen;
+	use window::render_scale::RenderScale;
+	use super::*;
+
+	/// A view without a window, keeping the settings changed by the menu.
+	struct TestView {
+		render_scale: RenderScale,
+		fullscreen: Fullscreen
+	}
+
+	impl ViewSettings for TestView {
+		fn render_scale(&self) -> &RenderScale {
+			&self.render_scale
+		}
+
+		fn set_render_scale(&mut self, render_scale: RenderScale) {
+			self.render_scale = render_scale;
+		}
+
+		fn fullscreen(&self) -> Fullscreen {
+			self.fullscreen
+		}
+
+		fn set_fullscreen(&mut self, fullscreen: Fullscreen) -> Result<(), String> {
+			self.fullscreen = fullscreen;
+			Ok(())
+		}
+	}
+
+	#[test]
+	fn only_choosing_triggers_items() {
+		let game = Game::new(Map::new("RRRR\nR  R\nRRRR"), Player {position: Point {x: 1.5, y: 1.5}, direction: 0.0});
+		let mut renderer = Renderer::new(game, 67.0, 100.0, 0.25, Profiler::new(false));
+		let mut view = TestView {render_scale: RenderScale::native(), fullscreen: Fullscreen::Off};
+		let mut settings = Settings::load(Config::new(), "settings.cfg", &mut view, &mut renderer).unwrap();
+		let mut menus = Menus::new();
+
+		// Title screen: Start, Settings, Quit
+		menus.open(Screen::Title, &view, &mut renderer, &settings);
+		for &selected in [1, 2].iter() {
+			renderer.menu.selected = selected;
+			for &input in [MenuInput::Decrease, MenuInput::Increase].iter() {
+				assert_eq!(menus.handle(input, &mut view, &mut renderer, &mut settings), MenuAction::None);
+				assert_eq!(renderer.menu.title, "Ray Casting Demo");
+			}
+		}
+		assert_eq!(menus.handle(MenuInput::Choose, &mut view, &mut renderer, &mut settings), MenuAction::Quit);
+
+		// Maps screen: the built-in map comes first
+		menus.open(Screen::Maps, &view, &mut renderer, &settings);
+		assert_eq!(menus.handle(MenuInput::Increase, &mut view, &mut renderer, &mut settings), MenuAction::None);
+		assert_eq!(menus.handle(MenuInput::Choose, &mut view, &mut renderer, &mut settings), MenuAction::LoadMap(None));
+	}
+}
@@ -0,0 +1,25 @@
+pub mod menus;
+pub mod settings;
+pub mod variables;
+
+use game::Game;
+use game::map::Map;
+use game::player::Player;
+use std::fs;
+use utils::geometry::Point;
+
+/// Loads a map and places the player within it.
+///
+/// # Parameters:
+///		- path:		The path of the map file, `None` for the built-in map
+pub fn load_game(path: &Option<String>) -> Result<Game, String> {
+	match *path {
+		None => Ok(Game::new(Map::new(include_str!("../../assets/map.txt")), Player {position: Point {x: 4.5, y: 5.5}, direction: 0.0})),
+		Some(ref path) => {
+			let map_string = fs::read_to_string(path).map_err(|error| format!("Could not read the map {}: {}", path, error))?;
+			let map = Map::parse(&map_string).map_err(|error| format!("Invalid map {}: {}", path, error))?;
+			let position = map.start_position().ok_or_else(|| format!("The map {} has no room for the player", path))?;
+			Ok(Game::new(map, Player {position, direction: 0.0}))
+		}
+	}
+}
@@ -0,0 +1,274 @@
+use renderer::Renderer;
+use renderer::fog::Fog;
+use renderer::fog::FogCurve;
+use utils::color::RGBColor;
+use utils::config::Config;
+use window::ViewSettings;
+use window::config::parse_switch;
+use window::event::Keycode;
+use window::render_scale::Filtering;
+use window::render_scale::RenderScale;
+use window::render_scale::Resolution;
+use window::render_scale::Upscaling;
+
+/// The actions of the player that can be bound to keys.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Action {
+	MoveForward,
+	MoveBackward,
+	TurnLeft,
+	TurnRight
+}
+
+/// The settings that can be changed in the menu. They are stored in the configuration file. Settings of the renderer and the window are
+/// applied to them directly, only the remaining settings are kept here.
+pub struct Settings {
+	/// How fast the player turns when the mouse is moved (1.0: default speed)
+	pub mouse_sensitivity: f64,
+
+	/// The path of the current map, `None` for the built-in map
+	pub map: Option<String>,
+
+	/// The directory containing the maps that can be chosen in the menu
+	pub map_directory: String,
+
+	/// The keys bound to the actions of the player
+	bindings: Vec<(Action, Keycode)>,
+
+	/// The content of the configuration file. Only contains the values from the file, not the ones given on the command line.
+	config: Config,
+
+	/// The path of the configuration file
+	path: String
+}
+
+impl Action {
+	/// All actions, in the order they are shown in the menu
+	pub const ALL: [Action; 4] = [Action::MoveForward, Action::MoveBackward, Action::TurnLeft, Action::TurnRight];
+
+	/// The name of the action shown in the menu
+	pub fn label(&self) -> &'static str {
+		match *self {
+			Action::MoveForward => "Move forward",
+			Action::MoveBackward => "Move backward",
+			Action::TurnLeft => "Turn left",
+			Action::TurnRight => "Turn right"
+		}
+	}
+
+	/// The key of the action's binding in the configuration file
+	fn config_key(&self) -> &'static str {
+		match *self {
+			Action::MoveForward => "controls.move_forward",
+			Action::MoveBackward => "controls.move_backward",
+			Action::TurnLeft => "controls.turn_left",
+			Action::TurnRight => "controls.turn_right"
+		}
+	}
+
+	/// The key bound to the action by default
+	fn default_keycode(&self) -> Keycode {
+		match *self {
+			Action::MoveForward => Keycode::Up,
+			Action::MoveBackward => Keycode::Down,
+			Action::TurnLeft => Keycode::Left,
+			Action::TurnRight => Keycode::Right
+		}
+	}
+}
+
+/// Reads the settings of the fog from the configuration file. The end of the fog has to be behind its start.
+///
+/// # Parameters:
+///		- config:		The content of the configuration file
+///		- fog:			The fog that is configured
+fn read_fog(config: &Config, fog: &mut Fog) -> Result<(), String> {
+	let number = |value: &str| value.parse::<f64>().ok().filter(|number| number.is_finite());
+
+	let start = config.convert("renderer.fog.start", number)?.unwrap_or(fog.start);
+	let end = config.convert("renderer.fog.end", number)?.unwrap_or(fog.end);
+	if end <= start {
+		return Err(format!("renderer.fog.end ({}) has to be greater than renderer.fog.start ({})", end, start));
+	}
+
+	fog.enabled = config.convert("renderer.fog.enabled", parse_switch)?.unwrap_or(fog.enabled);
+	fog.color = config.convert("renderer.fog.color", RGBColor::parse)?.unwrap_or_else(|| fog.color.clone());
+	fog.start = start;
+	fog.end = end;
+	fog.curve = config.convert("renderer.fog.curve", FogCurve::parse)?.unwrap_or(fog.curve);
+	Ok(())
+}
+
+/// Whether a key is handled before the bound actions, e.g. for toggling the minimap. Such keys cannot be bound to an action, since
+/// pressing them would trigger both.
+pub fn is_reserved(keycode: Keycode) -> bool {
+	match keycode {
+		Keycode::Escape | Keycode::Backquote | Keycode::Tab | Keycode::Home | Keycode::PageUp | Keycode::PageDown
+			| Keycode::F1 | Keycode::F2 | Keycode::F3 | Keycode::F4 | Keycode::F5 | Keycode::F6 | Keycode::F11
+			| Keycode::C | Keycode::F | Keycode::H | Keycode::I | Keycode::M | Keycode::N | Keycode::LeftBracket | Keycode::RightBracket => true,
+
+		// E/Q: Fly up/down
+		#[cfg(feature = "debug")]
+		Keycode::E | Keycode::Q => true,
+
+		_ => false
+	}
+}
+
+impl Settings {
+	/// Reads the settings from the configuration file and applies the settings of the renderer and the view.
+	///
+	/// # Parameters:
+	///		- config:		The content of the configuration file
+	///		- path:			The path of the configuration file, where changed settings are written to
+	///		- view:			The view whose render scale is configured
+	///		- renderer:		The renderer that is configured
+	pub fn load(config: Config, path: &str, view: &mut dyn ViewSettings, renderer: &mut Renderer) -> Result<Settings, String> {
+		let number = |value: &str| value.parse::<f64>().ok().filter(|number| number.is_finite());
+
+		renderer.field_of_view = config.convert("renderer.field_of_view", number)?.unwrap_or(renderer.field_of_view);
+		renderer.illumination_radius = config.convert("renderer.illumination_radius", number)?.unwrap_or(renderer.illumination_radius);
+		renderer.minimum_light = config.convert("renderer.minimum_light", number)?.unwrap_or(renderer.minimum_light);
+
+		// The fog is only configured by the file (or the console), it is not shown in the menu
+		read_fog(&config, &mut renderer.fog)?;
+
+		let mut render_scale = view.render_scale().clone();
+		render_scale.resolution = config.convert("renderer.resolution", Resolution::parse)?.unwrap_or(render_scale.resolution);
+		render_scale.upscaling = config.convert("renderer.upscaling", Upscaling::parse)?.unwrap_or(render_scale.upscaling);
+		render_scale.filtering = config.convert("renderer.filtering", Filtering::parse)?.unwrap_or(render_scale.filtering);
+		view.set_render_scale(render_scale);
+
+		Settings::read(config, path)
+	}
+
+	/// Reads the settings kept here from the configuration file.
+	///
+	/// # Parameters:
+	///		- config:		The content of the configuration file
+	///		- path:			The path of the configuration file, where changed settings are written to
+	fn read(config: Config, path: &str) -> Result<Settings, String> {
+		let number = |value: &str| value.parse::<f64>().ok().filter(|number| number.is_finite());
+		let bindable = |name: &str| Keycode::from_name(name).filter(|keycode| !is_reserved(*keycode));
+
+		let mut bindings = Vec::new();
+		for action in Action::ALL.iter() {
+			let keycode = config.convert(action.config_key(), bindable)?.unwrap_or_else(|| action.default_keycode());
+			bindings.push((*action, keycode));
+		}
+
+		let mouse_sensitivity = config.convert("controls.mouse_sensitivity", number)?.unwrap_or(1.0);
+		let map = config.get("game.map").filter(|map| *map != "default").map(|map| map.to_string());
+		let map_directory = config.get("game.map_directory").unwrap_or("assets/maps").to_string();
+
+		Ok(Settings {mouse_sensitivity, map, map_directory, bindings, config, path: path.to_string()})
+	}
+
+	/// The key bound to an action
+	pub fn binding(&self, action: Action) -> Keycode {
+		self.bindings.iter().find(|&&(bound_action, _)| bound_action == action).map_or(action.default_keycode(), |&(_, keycode)| keycode)
+	}
+
+	/// Binds a key to an action. If the key was bound to another action, the actions swap their keys. Reserved keys are rejected.
+	pub fn set_binding(&mut self, action: Action, keycode: Keycode) -> Result<(), String> {
+		if is_reserved(keycode) {
+			return Err(format!("{} is already used for another function", keycode));
+		}
+
+		let previous = self.binding(action);
+
+		for binding in self.bindings.iter_mut() {
+			if binding.0 == action {
+				binding.1 = keycode;
+			}
+			else if binding.1 == keycode {
+				binding.1 = previous;
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Writes the current settings to the configuration file.
+	///
+	/// # Parameters:
+	///		- view:			The view whose render scale and fullscreen mode are stored
+	///		- renderer:		The renderer whose settings are stored
+	pub fn save(&mut self, view: &dyn ViewSettings, renderer: &Renderer) -> Result<(), String> {
+		let RenderScale {ref resolution, upscaling, filtering} = *view.render_scale();
+
+		self.config.set("renderer.field_of_view", &renderer.field_of_view.to_string());
+		self.config.set("renderer.illumination_radius", &renderer.illumination_radius.to_string());
+		self.config.set("renderer.minimum_light", &renderer.minimum_light.to_string());
+		self.config.set("renderer.resolution", &resolution.to_string());
+		self.config.set("renderer.upscaling", &upscaling.to_string());
+		self.config.set("renderer.filtering", &filtering.to_string());
+		self.config.set("window.fullscreen", &view.fullscreen().to_string());
+		self.write();
+
+		self.config.save(&self.path)
+	}
+
+	/// Writes the settings kept here to the content of the configuration file.
+	fn write(&mut self) {
+		self.config.set("controls.mouse_sensitivity", &self.mouse_sensitivity.to_string());
+		self.config.set("game.map", self.map.as_ref().map_or("default", |map| map.as_str()));
+
+		for &(action, keycode) in self.bindings.iter() {
+			self.config.set(action.config_key(), &keycode.to_string());
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn rejects_fog_ending_before_its_start() {
+		let mut fog = Fog::new();
+		read_fog(&Config::parse("renderer.fog.start = 4\nrenderer.fog.end = 9").unwrap(), &mut fog).unwrap();
+		assert_eq!((fog.start, fog.end), (4.0, 9.0));
+
+		assert!(read_fog(&Config::parse("renderer.fog.end = 4").unwrap(), &mut fog).is_err());
+		assert!(read_fog(&Config::parse("renderer.fog.start = 12").unwrap(), &mut fog).is_err());
+		assert_eq!((fog.start, fog.end), (4.0, 9.0));
+	}
+
+	#[test]
+	fn swaps_bindings_and_rejects_reserved_keys() {
+		let mut settings = Settings::read(Config::new(), "settings.cfg").unwrap();
+
+		settings.set_binding(Action::MoveForward, Keycode::W).unwrap();
+		assert_eq!(settings.binding(Action::MoveForward), Keycode::W);
+		assert_eq!(settings.binding(Action::MoveBackward), Keycode::Down);
+
+		// The key of the other action moves to the previous key of the rebound action
+		settings.set_binding(Action::MoveBackward, Keycode::W).unwrap();
+		assert_eq!(settings.binding(Action::MoveBackward), Keycode::W);
+		assert_eq!(settings.binding(Action::MoveForward), Keycode::Down);
+
+		assert!(settings.set_binding(Action::TurnLeft, Keycode::M).is_err());
+		assert_eq!(settings.binding(Action::TurnLeft), Keycode::Left);
+	}
+
+	#[test]
+	fn reads_saved_settings() {
+		let path = std::env::temp_dir().join("raycaster-settings-test.cfg").to_string_lossy().into_owned();
+		let mut settings = Settings::read(Config::new(), &path).unwrap();
+		settings.mouse_sensitivity = 2.5;
+		settings.map = Some("assets/maps/crypt.txt".to_string());
+		settings.set_binding(Action::TurnLeft, Keycode::A).unwrap();
+		settings.write();
+		settings.config.save(&path).unwrap();
+
+		let loaded = Settings::read(Config::load(&path).unwrap(), &path).unwrap();
+		std::fs::remove_file(&path).unwrap();
+		assert_eq!(loaded.mouse_sensitivity, 2.5);
+		assert_eq!(loaded.map, Some("assets/maps/crypt.txt".to_string()));
+		assert_eq!(loaded.bindings, settings.bindings);
+
+		// Reserved keys cannot be bound in the file either
+		assert!(Settings::read(Config::parse("controls.turn_left = Tab").unwrap(), &path).is_err());
+	}
+}
@@ -0,0 +1,125 @@
+use renderer::Renderer;
+use renderer::fog::FogCurve;
+use utils::color::RGBColor;
+use window::config::parse_switch;
+
+/// A console variable bound to a setting of the renderer. Entering its name shows the value, entering its name and a value changes it.
+pub struct Variable {
+	/// The name entered in the console
+	pub name: &'static str,
+
+	/// What the variable changes, as shown by `help`
+	pub description: &'static str,
+
+	/// Reads the value from the renderer
+	pub get: fn(&Renderer) -> String,
+
+	/// Writes a value to the renderer. Returns `None` if the value is invalid.
+	pub set: fn(&mut Renderer, &str) -> Option<()>
+}
+
+/// The variables of the console. The focal length of the projection is derived from the field of view.
+pub const VARIABLES: [Variable; 12] = [
+	Variable {
+		name: "field_of_view", description: "The horizontal field of view in degrees (1 to 179)",
+		get: |renderer| renderer.field_of_view.to_string(),
+		set: |renderer, value| parse_number(value, 1.0, 179.0).map(|value| renderer.field_of_view = value)
+	},
+	Variable {
+		name: "illumination_radius", description: "The radius around the player where walls appear illuminated",
+		get: |renderer| renderer.illumination_radius.to_string(),
+		set: |renderer, value| parse_number(value, 0.1, 1000.0).map(|value| renderer.illumination_radius = value)
+	},
+	Variable {
+		name: "minimum_light", description: "The minimum environment light (0 to 1)",
+		get: |renderer| renderer.minimum_light.to_string(),
+		set: |renderer, value| parse_number(value, 0.0, 1.0).map(|value| renderer.minimum_light = value)
+	},
+	Variable {
+		name: "thread_count", description: "The number of threads rendering a frame",
+		get: |renderer| renderer.thread_count.to_string(),
+		set: |renderer, value| value.parse().ok().filter(|count| *count > 0).map(|count| renderer.thread_count = count)
+	},
+	Variable {
+		name: "fog.enabled", description: "Whether the scene fades to the fog color with the distance (on or off)",
+		get: |renderer| switch_name(renderer.fog.enabled),
+		set: |renderer, value| parse_switch(value).map(|enabled| renderer.fog.enabled = enabled)
+	},
+	Variable {
+		name: "fog.color", description: "The color of the fog (e.g. #b4b4b4)",
+		get: |renderer| renderer.fog.color.to_string(),
+		set: |renderer, value| RGBColor::parse(value).map(|color| renderer.fog.color = color)
+	},
+	Variable {
+		name: "fog.start", description: "The distance where the fog begins (less than fog.end)",
+		get: |renderer| renderer.fog.start.to_string(),
+		set: |renderer, value| parse_number(value, 0.0, 1000.0).filter(|start| *start < renderer.fog.end).map(|start| renderer.fog.start = start)
+	},
+	Variable {
+		name: "fog.end", description: "The distance where the fog is (almost) complete (greater than fog.start)",
+		get: |renderer| renderer.fog.end.to_string(),
+		set: |renderer, value| parse_number(value, 0.0, 1000.0).filter(|end| *end > renderer.fog.start).map(|end| renderer.fog.end = end)
+	},
+	Variable {
+		name: "fog.curve", description: "How the fog thickens (linear, exponential or exponential_squared)",
+		get: |renderer| renderer.fog.curve.to_string(),
+		set: |renderer, value| FogCurve::parse(value).map(|curve| renderer.fog.curve = curve)
+	},
+	Variable {
+		name: "hud.visible", description: "Whether the HUD is shown (on or off)",
+		get: |renderer| switch_name(renderer.hud.visible),
+		set: |renderer, value| parse_switch(value).map(|visible| renderer.hud.visible = visible)
+	},
+	Variable {
+		name: "minimap.visible", description: "Whether the minimap is shown (on or off)",
+		get: |renderer| switch_name(renderer.minimap.visible),
+		set: |renderer, value| parse_switch(value).map(|visible| renderer.minimap.visible = visible)
+	},
+	Variable {
+		name: "profiler.visible", description: "Whether the performance overlay is shown (on or off)",
+		get: |renderer| switch_name(renderer.profiler.visible),
+		set: |renderer, value| parse_switch(value).map(|visible| renderer.profiler.visible = visible)
+	}
+];
+
+/// Parses a number within a range.
+pub fn parse_number(value: &str, minimum: f64, maximum: f64) -> Option<f64> {
+	value.parse::<f64>().ok().filter(|number| *number >= minimum && *number <= maximum)
+}
+
+/// The name of a switch's state, as understood by `parse_switch`
+pub fn switch_name(on: bool) -> String {
+	if on { "on".to_string() } else { "off".to_string() }
+}
+
+#[cfg(test)]
+mod tests {
+	use game::Game;
+	use game::map::Map;
+	use game::player::Player;
+	use renderer::profiler::Profiler;
+	use utils::geometry::Point;
+	use super::*;
+
+	/// Changes a console variable, returns whether the value was accepted.
+	fn set(renderer: &mut Renderer, name: &str, value: &str) -> bool {
+		let variable = VARIABLES.iter().find(|variable| variable.name == name).unwrap();
+		(variable.set)(renderer, value).is_some()
+	}
+
+	#[test]
+	fn keeps_fog_end_behind_start() {
+		let game = Game::new(Map::new("RRRR\nR  R\nRRRR"), Player {position: Point {x: 1.5, y: 1.5}, direction: 0.0});
+		let mut renderer = Renderer::new(game, 67.0, 100.0, 0.25, Profiler::new(false));
+		renderer.fog.start = 2.0;
+		renderer.fog.end = 20.0;
+
+		assert!(!set(&mut renderer, "fog.start", "20"));
+		assert!(!set(&mut renderer, "fog.end", "1.5"));
+		assert_eq!((renderer.fog.start, renderer.fog.end), (2.0, 20.0));
+
+		assert!(set(&mut renderer, "fog.start", "5"));
+		assert!(set(&mut renderer, "fog.end", "8"));
+		assert_eq!((renderer.fog.start, renderer.fog.end), (5.0, 8.0));
+	}
+}
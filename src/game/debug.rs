@@ -0,0 +1,138 @@
+use game::Game;
+use game::map::Tile;
+use game::map::TilePosition;
+use utils::geometry::Point;
+
+/// Movement modes for inspecting maps, which ignore the rules of the game. Only available with the `debug` feature.
+pub struct DebugMovement {
+	/// Whether the player can move through walls
+	pub noclip: bool,
+
+	/// Whether the player's eyes can be moved up and down instead of staying at half the wall height
+	pub fly: bool,
+
+	/// The height of the player's eyes while flying (0: floor, 1: ceiling)
+	pub eye_height: f64
+}
+
+impl DebugMovement {
+	/// The lowest and highest eye height while flying. The walls are only drawn correctly between the floor and the ceiling.
+	const EYE_HEIGHT_LIMITS: (f64, f64) = (0.01, 0.99);
+
+	/// Creates the default movement: the player collides with walls and doesn't fly.
+	pub fn new() -> DebugMovement {
+		DebugMovement {noclip: false, fly: false, eye_height: Game::EYE_HEIGHT}
+	}
+}
+
+impl Default for DebugMovement {
+	fn default() -> DebugMovement {
+		DebugMovement::new()
+	}
+}
+
+// Methods related to the debug movement
+impl Game {
+	/// Places the player at a position. Returns an error if the position is outside of the map or inside a wall (unless noclip is
+	/// enabled).
+	///
+	/// # Parameters:
+	///		- `position`:	The new position of the player
+	pub fn teleport_player(&mut self, position: Point) -> Result<(), String> {
+		let tile_position = TilePosition::containing(&position);
+		if tile_position.x < 0 || tile_position.x as usize >= self.map.width() || tile_position.y < 0 || tile_position.y as usize >= self.map.height() {
+			return Err(format!("The position {:.1}, {:.1} is outside of the map", position.x, position.y));
+		}
+
+		match self.map.tile(&tile_position) {
+			Tile::Wall(_) if !self.debug_movement.noclip => Err(format!("The position {:.1}, {:.1} is inside a wall", position.x, position.y)),
+			_ => { self.player.position = position; Ok(()) }
+		}
+	}
+
+	/// Places the player at the center of a tile. Returns an error if the tile is outside of the map or a wall (unless noclip is
+	/// enabled).
+	///
+	/// # Parameters:
+	///		- `tile`:		The tile the player should be placed on
+	pub fn teleport_player_to_tile(&mut self, tile: &TilePosition) -> Result<(), String> {
+		self.teleport_player(Point {x: tile.x as f64 + 0.5, y: tile.y as f64 + 0.5})
+	}
+
+	/// Lets the player fly or land. Landing returns the player's eyes to the normal height, so the next flight starts from there.
+	///
+	/// # Parameters:
+	///		- `fly`:		Whether the player should fly
+	pub fn set_flying(&mut self, fly: bool) {
+		self.debug_movement.fly = fly;
+		if !fly {
+			self.debug_movement.eye_height = Game::EYE_HEIGHT;
+		}
+	}
+
+	/// Moves the player's eyes up or down while flying. Does nothing if the player doesn't fly.
+	///
+	/// # Parameters:
+	///		- `distance`:	The distance relative to the height of the walls (positive: up, negative: down)
+	pub fn fly_player(&mut self, distance: f64) {
+		if self.debug_movement.fly {
+			let (lowest, highest) = DebugMovement::EYE_HEIGHT_LIMITS;
+			self.debug_movement.eye_height = (self.debug_movement.eye_height + distance).clamp(lowest, highest);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use game::map::Map;
+	use game::player::Player;
+
+	fn game() -> Game {
+		Game::new(Map::new("RRRR\nR RR\nR  R\nRRRR"), Player {position: Point {x: 1.5, y: 1.5}, direction: 0.0})
+	}
+
+	#[test]
+	fn moves_through_walls_with_noclip() {
+		let mut game = game();
+		game.move_player(1.0);
+		assert_eq!((game.player.position.x, game.player.position.y), (1.5, 1.5));
+
+		game.debug_movement.noclip = true;
+		game.move_player(1.0);
+		assert_eq!((game.player.position.x, game.player.position.y), (2.5, 1.5));
+	}
+
+	#[test]
+	fn teleports_to_empty_tiles() {
+		let mut game = game();
+		assert!(game.teleport_player_to_tile(&TilePosition {x: 2, y: 1}).is_err());
+		assert!(game.teleport_player_to_tile(&TilePosition {x: 4, y: 2}).is_err());
+		assert!(game.teleport_player_to_tile(&TilePosition {x: 2, y: 2}).is_ok());
+		assert_eq!((game.player.position.x, game.player.position.y), (2.5, 2.5));
+
+		game.debug_movement.noclip = true;
+		assert!(game.teleport_player_to_tile(&TilePosition {x: 2, y: 1}).is_ok());
+		assert!(game.teleport_player_to_tile(&TilePosition {x: -1, y: 1}).is_err());
+	}
+
+	#[test]
+	fn flies_between_floor_and_ceiling() {
+		let mut game = game();
+		game.fly_player(0.2);
+		assert_eq!(game.eye_height(), Game::EYE_HEIGHT);
+
+		game.set_flying(true);
+		game.fly_player(0.2);
+		assert!((game.eye_height() - 0.7).abs() < 1e-9);
+		game.fly_player(1.0);
+		assert_eq!(game.eye_height(), 0.99);
+
+		game.set_flying(false);
+		assert_eq!(game.eye_height(), Game::EYE_HEIGHT);
+
+		// The next flight starts at the normal eye height
+		game.set_flying(true);
+		assert_eq!(game.eye_height(), Game::EYE_HEIGHT);
+	}
+}
@@ -0,0 +1,157 @@
+use game::map::Face;
+use game::map::TilePosition;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::atomic::AtomicU8;
+use std::sync::atomic::Ordering;
+
+/// Remembers which tiles and wall faces of a map the player has already seen.
+///
+/// Tiles are marked while the scene is rendered, which happens on several threads at once. Therefore, each tile is stored as
+/// an atomic set of flags that can be marked through a shared reference.
+pub struct Exploration {
+	/// The flags of each tile, row by row
+	tiles: Vec<AtomicU8>,
+
+	/// The number of tiles in a row
+	width: usize,
+
+	/// The number of rows
+	height: usize
+}
+
+/// The flag marking the floor of a tile as seen
+const FLOOR: u8 = 1 << 4;
+
+impl Exploration {
+	/// Creates an exploration state for a map of the given size where nothing has been seen yet.
+	pub fn new(width: usize, height: usize) -> Exploration {
+		Exploration {tiles: (0..width * height).map(|_| AtomicU8::new(0)).collect(), width, height}
+	}
+
+	/// Marks the floor of a tile as seen.
+	pub fn mark_tile(&self, position: &TilePosition) {
+		self.mark(position, FLOOR);
+	}
+
+	/// Marks a face of a wall tile as seen.
+	pub fn mark_face(&self, position: &TilePosition, face: Face) {
+		self.mark(position, Exploration::face_flag(face));
+	}
+
+	/// Whether the floor of a tile has been seen.
+	pub fn is_tile_seen(&self, position: &TilePosition) -> bool {
+		self.flags(position) & FLOOR != 0
+	}
+
+	/// Whether a face of a wall tile has been seen.
+	pub fn is_face_seen(&self, position: &TilePosition, face: Face) -> bool {
+		self.flags(position) & Exploration::face_flag(face) != 0
+	}
+
+	/// Whether any face of a wall tile has been seen.
+	pub fn is_any_face_seen(&self, position: &TilePosition) -> bool {
+		self.flags(position) & !FLOOR != 0
+	}
+
+	/// Sets a flag of a tile. Positions outside the map are ignored.
+	fn mark(&self, position: &TilePosition, flag: u8) {
+		if let Some(tile) = self.index(position).map(|index| &self.tiles[index]) {
+			// Most tiles are seen again and again, so avoid writing to memory shared between threads if possible.
+			if tile.load(Ordering::Relaxed) & flag == 0 {
+				tile.fetch_or(flag, Ordering::Relaxed);
+			}
+		}
+	}
+
+	/// The flags of a tile. Positions outside the map have never been seen.
+	fn flags(&self, position: &TilePosition) -> u8 {
+		self.index(position).map_or(0, |index| self.tiles[index].load(Ordering::Relaxed))
+	}
+
+	/// The index of a tile inside the flag vector
+	fn index(&self, position: &TilePosition) -> Option<usize> {
+		if position.x < 0 || position.y < 0 || position.x as usize >= self.width || position.y as usize >= self.height {
+			None
+		}
+		else {
+			Some(position.y as usize * self.width + position.x as usize)
+		}
+	}
+
+	/// The flag marking a face of a tile as seen
+	fn face_flag(face: Face) -> u8 {
+		match face {
+			Face::North => 1 << 0,
+			Face::East => 1 << 1,
+			Face::South => 1 << 2,
+			Face::West => 1 << 3
+		}
+	}
+}
+
+impl fmt::Display for Exploration {
+	/// Writes the exploration state as one line per row with a base-32 digit per tile, so it can be persisted along with a map.
+	fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		for row in self.tiles.chunks(self.width.max(1)) {
+			for tile in row {
+				let flags = tile.load(Ordering::Relaxed) as u32;
+				write!(formatter, "{}", std::char::from_digit(flags, 32).unwrap_or('0'))?;
+			}
+			writeln!(formatter)?;
+		}
+
+		Ok(())
+	}
+}
+
+impl FromStr for Exploration {
+	type Err = String;
+
+	/// Restores an exploration state from the string created by its `Display` implementation. Each line represents one row of the map and each
+	/// base-32 digit the flags of a tile. All rows must have the same length.
+	fn from_str(exploration_string: &str) -> Result<Exploration, String> {
+		let height = exploration_string.lines().count();
+		let width = exploration_string.lines().next().map_or(0, |line| line.chars().count());
+		let exploration = Exploration::new(width, height);
+
+		for (y, line) in exploration_string.lines().enumerate() {
+			if line.chars().count() != width {
+				return Err(format!("Row {} has {} tiles instead of {}", y + 1, line.chars().count(), width));
+			}
+
+			for (x, digit) in line.chars().enumerate() {
+				let flags = digit.to_digit(32).ok_or_else(|| format!("Invalid exploration flags '{}' in row {}", digit, y + 1))?;
+				exploration.tiles[y * width + x].store(flags as u8, Ordering::Relaxed);
+			}
+		}
+
+		Ok(exploration)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn restores_formatted_exploration() {
+		let exploration = Exploration::new(3, 2);
+		exploration.mark_tile(&TilePosition {x: 1, y: 0});
+		exploration.mark_face(&TilePosition {x: 2, y: 1}, Face::West);
+		exploration.mark_face(&TilePosition {x: 2, y: 1}, Face::South);
+
+		let restored: Exploration = exploration.to_string().parse().unwrap();
+		assert_eq!((restored.width, restored.height), (3, 2));
+		assert_eq!(restored.to_string(), exploration.to_string());
+		assert!(restored.is_tile_seen(&TilePosition {x: 1, y: 0}) && !restored.is_tile_seen(&TilePosition {x: 0, y: 0}));
+		assert!(restored.is_face_seen(&TilePosition {x: 2, y: 1}, Face::West) && !restored.is_face_seen(&TilePosition {x: 2, y: 1}, Face::North));
+	}
+
+	#[test]
+	fn rejects_invalid_exploration() {
+		assert_eq!("0g0\n0w0".parse::<Exploration>().err(), Some("Invalid exploration flags 'w' in row 2".to_string()));
+		assert_eq!("000\n00".parse::<Exploration>().err(), Some("Row 2 has 2 tiles instead of 3".to_string()));
+		assert_eq!("00\n000".parse::<Exploration>().err(), Some("Row 2 has 3 tiles instead of 2".to_string()));
+	}
+}
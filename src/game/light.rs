@@ -0,0 +1,332 @@
+use game::map::Face;
+use game::map::Map;
+use game::map::Tile;
+use game::map::TilePosition;
+use std::collections::HashMap;
+use utils::color::LightColor;
+use utils::color::RGBColor;
+use utils::geometry::Angle;
+use utils::geometry::Point;
+
+/// A light source shining in all directions, e.g. a lamp placed in the map. Walls between the light and a surface cast shadows.
+#[derive(Clone)]
+pub struct Light {
+	/// The position of the light within the map. Lights inside walls are hidden by the wall.
+	pub position: Point,
+
+	/// The color of the light
+	pub color: RGBColor,
+
+	/// The distance up to which the light reaches
+	pub radius: f64,
+
+	/// The brightness of the light close to a surface facing it (1: the surface appears in its own color)
+	pub intensity: f64
+}
+
+/// A light evaluated while rendering, e.g. a flickering torch or the player's flashlight. Unlike baked lights, it can move and
+/// change its brightness.
+#[derive(Clone)]
+pub struct DynamicLight {
+	/// The position, color and reach of the light
+	pub light: Light,
+
+	/// How much the brightness varies over time (0: steady, 1: flickers between dark and full brightness)
+	pub flicker: f64,
+
+	/// The cone of a spot light, `None` for a light shining in all directions
+	pub spot: Option<Spot>,
+
+	/// Whether the light is switched on
+	pub enabled: bool,
+
+	/// The current brightness relative to the intensity of the light, changed by flickering
+	brightness: f64
+}
+
+/// The cone of a spot light.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Spot {
+	/// The direction the light shines to
+	pub direction: Angle,
+
+	/// The angle between the direction and the border of the cone, in radians
+	pub half_angle: f64
+}
+
+/// The light of the baked lights falling onto the faces of the walls. It is computed once when a map is loaded, so baked lights
+/// cost almost nothing while rendering.
+pub struct Lightmap {
+	/// The light at evenly spaced points along each lit face, by the position of the tile and the face
+	faces: HashMap<(isize, isize, Face), Vec<LightColor>>
+}
+
+impl Light {
+	/// The distance from a face at which shadows are tested, so the face's own wall doesn't cast a shadow
+	const SURFACE_OFFSET: f64 = 1e-3;
+
+	/// Parses the fields of a light definition in a map file: "<x> <y> <color> <radius> <intensity>" (e.g. "4.5 5.5 #ffc080 6 1").
+	pub fn parse(fields: &[&str]) -> Option<Light> {
+		let number = |field: &str| field.parse::<f64>().ok().filter(|number| number.is_finite() && *number >= 0.0);
+
+		match *fields {
+			[x, y, color, radius, intensity] => Some(Light {
+				position: Point {x: number(x)?, y: number(y)?},
+				color: RGBColor::parse(color)?,
+				radius: number(radius)?,
+				intensity: number(intensity)?
+			}),
+			_ => None
+		}
+	}
+
+	/// Determines the light falling onto a point of a wall face. The brightness falls off with the distance to the light and the angle
+	/// between the face and the light.
+	///
+	/// # Parameters:
+	///		- map:			The map whose walls cast shadows
+	///		- point:		The point on the face
+	///		- face:			The face of the wall tile
+	///		- brightness:	A factor for the intensity of the light (e.g. for flickering)
+	pub fn light_at(&self, map: &Map, point: &Point, face: Face, brightness: f64) -> LightColor {
+		let normal = face.normal();
+		let (delta_x, delta_y) = (self.position.x - point.x, self.position.y - point.y);
+		let distance = (delta_x * delta_x + delta_y * delta_y).sqrt();
+
+		// Faces turned away from the light and points out of reach stay dark
+		let facing = if distance > 0.0 { (delta_x * normal.x + delta_y * normal.y) / distance } else { 0.0 };
+		if distance >= self.radius || facing <= 0.0 || brightness <= 0.0 {
+			return LightColor::dark();
+		}
+
+		let surface = Point {x: point.x + normal.x * Light::SURFACE_OFFSET, y: point.y + normal.y * Light::SURFACE_OFFSET};
+		if !map.line_of_sight(&self.position, &surface) {
+			return LightColor::dark();
+		}
+
+		let falloff = 1.0 - distance / self.radius;
+		LightColor::new(&self.color, self.intensity * brightness * facing * falloff * falloff)
+	}
+}
+
+impl DynamicLight {
+	/// Creates a steady light shining in all directions.
+	pub fn new(light: Light) -> DynamicLight {
+		DynamicLight {light, flicker: 0.0, spot: None, enabled: true, brightness: 1.0}
+	}
+
+	/// Parses the fields of a torch definition in a map file: "<x> <y> <color> <radius> <intensity> <flicker>" (e.g.
+	/// "4.5 5.5 #ff8020 4 1 0.3").
+	pub fn parse(fields: &[&str]) -> Option<DynamicLight> {
+		let (flicker, light_fields) = fields.split_last()?;
+		let flicker = flicker.parse::<f64>().ok().filter(|flicker| *flicker >= 0.0 && *flicker <= 1.0)?;
+
+		Some(DynamicLight {flicker, ..DynamicLight::new(Light::parse(light_fields)?)})
+	}
+
+	/// Creates a flashlight: a white spot light that is switched off. It has to be moved along with the player.
+	pub fn flashlight() -> DynamicLight {
+		let light = Light {position: Point {x: 0.0, y: 0.0}, color: RGBColor::white(), radius: 8.0, intensity: 1.2};
+		DynamicLight {spot: Some(Spot {direction: 0.0, half_angle: 0.35}), enabled: false, ..DynamicLight::new(light)}
+	}
+
+	/// Whether the light is switched on and flickers, which requires redrawing the scene continuously
+	pub fn is_flickering(&self) -> bool {
+		self.enabled && self.flicker > 0.0
+	}
+
+	/// Updates the brightness of a flickering light. The brightness follows a sum of sine waves, which looks random but is
+	/// reproducible. The waves are shifted by the light's position, so torches don't flicker in sync.
+	///
+	/// # Parameters:
+	///		- time:		The time since the start of the game in seconds
+	pub fn update(&mut self, time: f64) {
+		let phase = self.light.position.x * 12.9898 + self.light.position.y * 78.233;
+		let wave = ((time * 7.0 + phase).sin() + 0.5 * (time * 13.0 + 1.3 * phase).sin() + 0.25 * (time * 29.0 + 0.7 * phase).sin()) / 1.75;
+
+		self.brightness = 1.0 - self.flicker * (wave + 1.0) / 2.0;
+	}
+
+	/// Determines the light falling onto a point of a wall face, taking the cone of spot lights into account.
+	///
+	/// # Parameters:
+	///		- map:		The map whose walls cast shadows
+	///		- point:	The point on the face
+	///		- face:		The face of the wall tile
+	pub fn light_at(&self, map: &Map, point: &Point, face: Face) -> LightColor {
+		if !self.enabled {
+			return LightColor::dark();
+		}
+
+		// Spot lights fade out towards the border of their cone
+		let cone = match self.spot {
+			Some(spot) => {
+				let angle = (point.y - self.light.position.y).atan2(point.x - self.light.position.x) - spot.direction;
+				let border = spot.half_angle.cos();
+				((angle.cos() - border) / (1.0 - border)).clamp(0.0, 1.0)
+			},
+			None => 1.0
+		};
+
+		self.light.light_at(map, point, face, self.brightness * cone)
+	}
+}
+
+impl Lightmap {
+	/// The number of points per face the light is computed for. The light between them is interpolated.
+	const RESOLUTION: usize = 8;
+
+	/// Creates a lightmap without any light.
+	pub fn new() -> Lightmap {
+		Lightmap {faces: HashMap::new()}
+	}
+
+	/// Computes the light of baked lights falling onto all faces of a map's walls that are next to an empty tile.
+	///
+	/// # Parameters:
+	///		- map:		The map whose walls are lit and cast shadows
+	///		- lights:	The lights whose light is baked
+	pub fn bake(map: &Map, lights: &[Light]) -> Lightmap {
+		let mut lightmap = Lightmap::new();
+
+		for light in lights.iter() {
+			// Only the tiles within the reach of the light can be lit
+			let reach = |center: f64, size: usize| ((center - light.radius).floor().max(0.0) as isize)..((center + light.radius).ceil().min(size as f64) as isize);
+
+			for y in reach(light.position.y, map.height()) {
+				for x in reach(light.position.x, map.width()) {
+					let tile = TilePosition {x, y};
+					if map.tile(&tile) == Tile::Empty {
+						continue;
+					}
+
+					for &face in Face::ALL.iter().filter(|&&face| map.tile(&tile.neighbor(face)) == Tile::Empty) {
+						let samples: Vec<LightColor> = (0..Lightmap::RESOLUTION)
+							.map(|index| light.light_at(map, &face_point(&tile, face, (index as f64 + 0.5) / Lightmap::RESOLUTION as f64), face, 1.0))
+							.collect();
+
+						if samples.iter().any(|sample| *sample != LightColor::dark()) {
+							let face_samples = lightmap.faces.entry((x, y, face)).or_insert_with(|| vec![LightColor::dark(); Lightmap::RESOLUTION]);
+							for (face_sample, sample) in face_samples.iter_mut().zip(samples) {
+								*face_sample = face_sample.add(&sample);
+							}
+						}
+					}
+				}
+			}
+		}
+
+		lightmap
+	}
+
+	/// Determines the baked light falling onto a point of a wall face.
+	///
+	/// # Parameters:
+	///		- tile:		The position of the wall tile
+	///		- face:		The face of the wall tile
+	///		- point:	The point on the face
+	pub fn light_at(&self, tile: &TilePosition, face: Face, point: &Point) -> LightColor {
+		let samples = match self.faces.get(&(tile.x, tile.y, face)) {
+			Some(samples) => samples,
+			None => { return LightColor::dark(); }
+		};
+
+		// Interpolate between the two samples next to the point
+		let offset = match face {
+			Face::North | Face::South => point.x - tile.x as f64,
+			Face::East | Face::West => point.y - tile.y as f64
+		};
+		let position = (offset * Lightmap::RESOLUTION as f64 - 0.5).clamp(0.0, (Lightmap::RESOLUTION - 1) as f64);
+		let index = (position.floor() as usize).min(Lightmap::RESOLUTION - 2);
+		let fraction = position - index as f64;
+
+		samples[index].scale(1.0 - fraction).add(&samples[index + 1].scale(fraction))
+	}
+}
+
+impl Default for Lightmap {
+	fn default() -> Lightmap {
+		Lightmap::new()
+	}
+}
+
+/// Determines a point on the face of a tile.
+///
+/// # Parameters:
+///		- tile:		The position of the tile
+///		- face:		The face of the tile
+///		- offset:	The position along the face (0: west or north end, 1: east or south end)
+fn face_point(tile: &TilePosition, face: Face, offset: f64) -> Point {
+	let (x, y) = (tile.x as f64, tile.y as f64);
+
+	match face {
+		Face::North => Point {x: x + offset, y},
+		Face::South => Point {x: x + offset, y: y + 1.0},
+		Face::West => Point {x, y: y + offset},
+		Face::East => Point {x: x + 1.0, y: y + offset}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn lamp(x: f64, y: f64) -> Light {
+		Light {position: Point {x, y}, color: RGBColor::white(), radius: 5.0, intensity: 1.0}
+	}
+
+	#[test]
+	fn parses_light_definitions() {
+		let light = Light::parse(&["4.5", "5.5", "#ff8000", "6", "0.5"]).unwrap();
+		assert_eq!((light.position.x, light.position.y, light.color, light.radius, light.intensity), (4.5, 5.5, RGBColor {red: 255, green: 128, blue: 0}, 6.0, 0.5));
+
+		let torch = DynamicLight::parse(&["4.5", "5.5", "#ff8000", "6", "0.5", "0.3"]).unwrap();
+		assert_eq!(torch.flicker, 0.3);
+
+		assert!(Light::parse(&["4.5", "5.5", "orange", "6", "0.5"]).is_none());
+		assert!(Light::parse(&["4.5", "5.5", "#ff8000", "6"]).is_none());
+		assert!(DynamicLight::parse(&["4.5", "5.5", "#ff8000", "6", "0.5", "2"]).is_none());
+	}
+
+	#[test]
+	fn lights_faces_facing_the_light() {
+		// The lamp is north-east of a row of walls. The west face of the easternmost wall is next to another wall, so it isn't lit.
+		let map = Map::new("     \n RRR \n     ");
+		let lightmap = Lightmap::bake(&map, &[lamp(4.5, 0.5)]);
+
+		let lit = lightmap.light_at(&TilePosition {x: 3, y: 1}, Face::East, &Point {x: 4.0, y: 1.5});
+		assert!(lit.red > 0.0 && lit.red == lit.blue, "{:?}", lit);
+		assert_eq!(lightmap.light_at(&TilePosition {x: 3, y: 1}, Face::West, &Point {x: 3.0, y: 1.5}), LightColor::dark());
+		assert_eq!(lightmap.light_at(&TilePosition {x: 3, y: 1}, Face::South, &Point {x: 3.5, y: 2.0}), LightColor::dark());
+
+		// Faces farther away receive less light
+		let near = lightmap.light_at(&TilePosition {x: 3, y: 1}, Face::North, &Point {x: 3.9, y: 1.0});
+		let far = lightmap.light_at(&TilePosition {x: 1, y: 1}, Face::North, &Point {x: 1.1, y: 1.0});
+		assert!(near.red > far.red && far.red > 0.0, "{:?} {:?}", near, far);
+	}
+
+	#[test]
+	fn walls_cast_shadows() {
+		let map = Map::new("RRRRRRR\nR  R  R\nRRRRRRR");
+		let lightmap = Lightmap::bake(&map, &[lamp(1.5, 1.5)]);
+
+		assert!(lightmap.light_at(&TilePosition {x: 3, y: 1}, Face::West, &Point {x: 3.0, y: 1.5}).red > 0.0);
+		assert_eq!(lightmap.light_at(&TilePosition {x: 6, y: 1}, Face::West, &Point {x: 6.0, y: 1.5}), LightColor::dark());
+	}
+
+	#[test]
+	fn spot_lights_shine_within_their_cone() {
+		let map = Map::new("     \n    R\n     ");
+		let mut flashlight = DynamicLight::flashlight();
+		flashlight.light.position = Point {x: 0.5, y: 1.5};
+		let point = Point {x: 4.0, y: 1.5};
+
+		assert_eq!(flashlight.light_at(&map, &point, Face::West), LightColor::dark());
+
+		flashlight.enabled = true;
+		assert!(flashlight.light_at(&map, &point, Face::West).red > 0.0);
+
+		flashlight.spot = Some(Spot {direction: 1.0, half_angle: 0.35});
+		assert_eq!(flashlight.light_at(&map, &point, Face::West), LightColor::dark());
+	}
+}
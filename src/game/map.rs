@@ -1,7 +1,6 @@
+use game::light::DynamicLight;
+use game::light::Light;
 use utils::color::RGBColor;
-use utils::geometry::Angle;
-use utils::geometry::Axis;
-use utils::geometry::Direction;
 use utils::geometry::Point;
 
 /// Represents the map of the 3D maze.
@@ -9,8 +8,17 @@ pub struct Map {
 	/// The tiles of the map
 	tiles: Vec<Vec<Tile>>,
 
+	/// The number of tiles in the longest row of the map
+	width: usize,
+
 	/// The longest distance between two points within the map
-	max_distance: usize
+	max_distance: usize,
+
+	/// The lights whose light is baked into the lightmap when the map is loaded
+	lights: Vec<Light>,
+
+	/// The lights evaluated while rendering (e.g. flickering torches)
+	dynamic_lights: Vec<DynamicLight>
 }
 
 #[derive(PartialEq, Eq, Clone)]
@@ -19,15 +27,79 @@ pub enum Tile {
 	Wall(RGBColor)
 }
 
+/// The faces of a tile. The map's y-axis points south (i.e. downwards in the map file).
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum Face {
+	North,
+	East,
+	South,
+	West
+}
+
+impl Face {
+	/// All faces of a tile
+	pub const ALL: [Face; 4] = [Face::North, Face::East, Face::South, Face::West];
+
+	/// The unit vector pointing out of the tile through the face
+	pub fn normal(&self) -> Point {
+		match *self {
+			Face::North => Point {x: 0.0, y: -1.0},
+			Face::East => Point {x: 1.0, y: 0.0},
+			Face::South => Point {x: 0.0, y: 1.0},
+			Face::West => Point {x: -1.0, y: 0.0}
+		}
+	}
+}
+
 impl Map {
-	/// Creates a new map from the given string.
+	/// The distance between two points of a line tested by `line_of_sight`
+	const LINE_OF_SIGHT_STEP: f64 = 0.05;
+
+	/// Creates a new map from the given string. Lines starting with `light` or `torch` place lights in the map (see `parse`), invalid lights
+	/// are skipped.
 	///
 	/// # Parameters
     ///    - `map_string`:	A string representation of the map, whereas each line represents one row of the map and each character of a line represents a tile of a row.
 	///						Use the characters R,G,B,Y,O to designate a wall with a certain color. Use spaces to designate empty tiles. Do not use tabs.
 	pub fn new(map_string: &str) -> Map {
+		Map::read(map_string).0
+	}
+
+	/// Creates a new map from the given string like `new`, but returns an error if a light is invalid. Besides the rows of tiles, the
+	/// string may contain lines placing lights in the map:
+	///		- `light <x> <y> <color> <radius> <intensity>`:				A light whose light is baked into the walls (e.g. `light 4.5 5.5 #ffc080 6 1`)
+	///		- `torch <x> <y> <color> <radius> <intensity> <flicker>`:	A light evaluated while rendering, flickering by 0 to 1 (e.g. `torch 4.5 5.5 #ff8020 4 1 0.3`)
+	pub fn parse(map_string: &str) -> Result<Map, String> {
+		let (map, errors) = Map::read(map_string);
+
+		match errors.into_iter().next() {
+			Some(error) => Err(error),
+			None => Ok(map)
+		}
+	}
+
+	/// Reads the rows of tiles and the lights of a map. Returns the map and the errors of invalid lights, which are skipped.
+	fn read(map_string: &str) -> (Map, Vec<String>) {
+		let mut rows = Vec::new();
+		let mut lights = Vec::new();
+		let mut dynamic_lights = Vec::new();
+		let mut errors = Vec::new();
+
+		for (index, line) in map_string.lines().enumerate() {
+			let fields: Vec<&str> = line.split_whitespace().collect();
+			let expected = match fields.split_first() {
+				Some((&"light", fields)) => Light::parse(fields).map(|light| lights.push(light)).ok_or("light <x> <y> <color> <radius> <intensity>"),
+				Some((&"torch", fields)) => DynamicLight::parse(fields).map(|light| dynamic_lights.push(light)).ok_or("torch <x> <y> <color> <radius> <intensity> <flicker>"),
+				_ => { rows.push(line); Ok(()) }
+			};
+
+			if let Err(expected) = expected {
+				errors.push(format!("Line {}: Expected \"{}\", found \"{}\"", index + 1, expected, line.trim()));
+			}
+		}
+
 		// Go through the map line by line and create either tiles with a certain color or empty tiles.
-		let tiles: Vec<Vec<Tile>> = map_string.lines().map({|line|
+		let tiles: Vec<Vec<Tile>> = rows.iter().map({|line|
 			line.chars().map ({|field|
 				match field {
 					' ' =>	Tile::Empty,
@@ -46,7 +118,17 @@ impl Map {
 		let width = tiles.iter().fold(0, {|max_count, line| line.len().max(max_count)});
 		let max_distance = height + width;
 
-		Map {tiles, max_distance}
+		(Map {tiles, width, max_distance, lights, dynamic_lights}, errors)
+	}
+
+	/// The number of tiles in the longest row of the map
+	pub fn width(&self) -> usize {
+		self.width
+	}
+
+	/// The number of rows of the map
+	pub fn height(&self) -> usize {
+		self.tiles.len()
 	}
 
 	/// Returns the contents of an tile inside the map.
@@ -66,76 +148,157 @@ impl Map {
 		return line[position.x as usize].clone();
 	}
 
+	/// Finds a position for placing the player: the center of the first empty tile (row by row) that is not at the border of the map.
+	/// Returns `None` if the map has no such tile.
+	pub fn start_position(&self) -> Option<Point> {
+		for (y, row) in self.tiles.iter().enumerate().skip(1).take(self.height().saturating_sub(2)) {
+			for (x, tile) in row.iter().enumerate().skip(1).take(row.len().saturating_sub(2)) {
+				if *tile == Tile::Empty {
+					return Some(Point {x: x as f64 + 0.5, y: y as f64 + 0.5});
+				}
+			}
+		}
+
+		None
+	}
+
+	/// Whether the straight line between two points only crosses empty tiles. The line is sampled at short intervals, so it may slip
+	/// through the corner where two walls touch diagonally.
+	///
+	/// # Parameters:
+	///		- from:		The start of the line
+	///		- to:		The end of the line
+	pub fn line_of_sight(&self, from: &Point, to: &Point) -> bool {
+		let (delta_x, delta_y) = (to.x - from.x, to.y - from.y);
+		let steps = (((delta_x * delta_x + delta_y * delta_y).sqrt() / Map::LINE_OF_SIGHT_STEP).ceil() as usize).max(1);
+
+		(0..=steps).all(|step| {
+			let fraction = step as f64 / steps as f64;
+			self.tile(&TilePosition::containing(&Point {x: from.x + delta_x * fraction, y: from.y + delta_y * fraction})) == Tile::Empty
+		})
+	}
+
+	/// The lights whose light is baked into the lightmap when the map is loaded
+	pub fn lights(&self) -> &[Light] {
+		&self.lights
+	}
+
+	/// The lights evaluated while rendering (e.g. flickering torches)
+	pub fn dynamic_lights(&self) -> &[DynamicLight] {
+		&self.dynamic_lights
+	}
+
 	/// The longest distance between two points that can exist within the map
 	pub fn max_distance(&self) -> usize {
 		self.max_distance
 	}
 
-	/// Returns the light intensity of a wall at a certain point depending on the viewing angle.
+	/// Returns the light intensity of a wall depending on the face it is viewn from.
 	///
 	/// # Parameters:
-	///		- point:		The point of the wall from which the light intensity is queried.
-	///		- direction:	The direction the wall is viewn from
-	pub fn light_intensity_for_wall(point: Point, direction: Angle) -> f64 {
-		// Determine on which side of the wall the point resides.
-		let closest_axis = point.closest_grid_line_axis();
-		let viewing_direction = Direction::from_angle(&direction, &closest_axis);
-		
-		match closest_axis {
-			// The ray hit a wall that is parallel to the x-axis
-			Axis::X =>
-				match viewing_direction {
-					Direction::Increasing => 1.0,
-					Direction::Decreasing => 0.6
-				},
-
-			// The ray hit a wall that is parallel to the y-axis
-			Axis::Y =>
-				match viewing_direction {
-					Direction::Increasing => 0.4,
-					Direction::Decreasing => 0.8
-				}
+	///		- face:		The face of the wall's tile that is visible
+	pub fn light_intensity_for_wall(face: Face) -> f64 {
+		match face {
+			Face::West => 1.0,
+			Face::East => 0.6,
+			Face::North => 0.4,
+			Face::South => 0.8
 		}
-	}	
+	}
 }
 
 /// The position of a tile within the map
+#[derive(PartialEq, Eq, Clone, Debug)]
 pub struct TilePosition {
-	x: isize,
-	y: isize
+	pub x: isize,
+	pub y: isize
 }
 
 impl TilePosition {
-	/// Rounds a point to a tile position. Makes sure that ambiguous points - i.e. points between two tiles - are properly rounded using the given angle.
-	///
-	/// # Parameters:
-	///		- point:		The point that should be converted to a tile position.
-	///		- angle:		An angle used to resolve ambiguous points.
-	pub fn new(point: &Point, angle: &Angle) -> TilePosition {
-		let x = TilePosition::component_from_point(point, Axis::X, angle);
-		let y = TilePosition::component_from_point(point, Axis::Y, angle);
+	/// Returns the position of the neighboring tile behind a face of this tile.
+	pub fn neighbor(&self, face: Face) -> TilePosition {
+		match face {
+			Face::North => TilePosition {x: self.x, y: self.y - 1},
+			Face::East => TilePosition {x: self.x + 1, y: self.y},
+			Face::South => TilePosition {x: self.x, y: self.y + 1},
+			Face::West => TilePosition {x: self.x - 1, y: self.y}
+		}
+	}
 
-		TilePosition {x, y}
+	/// Returns the position of the tile containing the given point. Points on a grid line belong to the tile with the larger coordinate.
+	pub fn containing(point: &Point) -> TilePosition {
+		TilePosition {x: point.x.floor() as isize, y: point.y.floor() as isize}
 	}
+}
 
-	/// Rounds a single coordinate of a point to a single coordinate of a tile position. Makes sure that ambiguous coordinates - i.e. points between two
-	/// tiles - are properly rounded using the given angle.
-	///
-	/// # Parameters:
-	///		- point:		The point from which a component should be converted to a tile position component.
-	///		- axis:			The axis the point's component should be taken from
-	///		- angle:		An angle used to resolve ambiguous points.
-	fn component_from_point(point: &Point, axis: Axis, angle: &Angle) -> isize {
-		let mut point_component = point.component(&axis);
-
-		//if point_component == point_component as i64 as f64 {
-		if point_component.fract() == 0.0 {
-			match Direction::from_angle(angle, &axis) {
-				Direction::Increasing => {}
-				Direction::Decreasing => { point_component -= 1.0; }
-			}
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use proptest::prelude::*;
+
+	proptest! {
+		#[test]
+		fn tile_position_contains_point(x in -100.0..100.0f64, y in -100.0..100.0f64) {
+			let tile = TilePosition::containing(&Point {x, y});
+			prop_assert!(tile.x as f64 <= x && x < (tile.x + 1) as f64, "x = {}, tile = {:?}", x, tile);
+			prop_assert!(tile.y as f64 <= y && y < (tile.y + 1) as f64, "y = {}, tile = {:?}", y, tile);
+		}
+
+		#[test]
+		fn tile_position_on_grid_line_has_larger_coordinate(x in -100i32..100, y in -100i32..100) {
+			// Points on grid lines (and on the corners of tiles) belong to the tile right of (resp. below) them
+			prop_assert_eq!(TilePosition::containing(&Point {x: x as f64, y: y as f64}), TilePosition {x: x as isize, y: y as isize});
+		}
+
+		#[test]
+		fn tile_position_of_negative_coordinates_is_negative(x in -100.0..0.0f64, y in -100.0..0.0f64) {
+			// Rounding towards zero would put points between -1 and 0 into the tiles at 0
+			let tile = TilePosition::containing(&Point {x, y});
+			prop_assert!(tile.x < 0 && tile.y < 0, "tile = {:?}", tile);
 		}
 
-		point_component as isize
+		#[test]
+		fn points_outside_map_are_empty(x in -50.0..50.0f64, y in -50.0..50.0f64) {
+			let map = Map::new("RRR\nR R\nRRR");
+			prop_assume!(x < 0.0 || y < 0.0 || x >= 3.0 || y >= 3.0);
+			prop_assert!(map.tile(&TilePosition::containing(&Point {x, y})) == Tile::Empty, "point ({}, {})", x, y);
+		}
+	}
+
+	#[test]
+	fn tile_outside_map_is_empty() {
+		let map = Map::new("RR\nR ");
+		for &(x, y) in [(-1, 0), (0, -1), (2, 0), (0, 2), (1, 1), (-1, -1)].iter() {
+			assert!(map.tile(&TilePosition {x, y}) == Tile::Empty, "tile ({}, {})", x, y);
+		}
+		assert!(map.tile(&TilePosition {x: 0, y: 0}) == Tile::Wall(RGBColor::red()));
+	}
+
+	#[test]
+	fn start_position_is_first_inner_empty_tile() {
+		let map = Map::new("  RRRR\nRR R  \nRRRRRR");
+		let start = map.start_position().map(|point| (point.x, point.y));
+
+		assert_eq!(start, Some((2.5, 1.5)));
+		assert!(Map::new("   \n   ").start_position().is_none());
+	}
+
+	#[test]
+	fn reads_lights_between_rows() {
+		let map = Map::parse("RRRR\nlight 1.5 1.5 #ffc080 6 1\nR  R\ntorch 2.5 1.5 #ff8020 4 1 0.3\nRRRR").unwrap();
+		assert_eq!((map.width(), map.height()), (4, 3));
+		assert!(map.tile(&TilePosition {x: 1, y: 1}) == Tile::Empty);
+		assert_eq!((map.lights().len(), map.dynamic_lights().len()), (1, 1));
+
+		let invalid = "RRRR\nR  R\nlight 1.5 1.5 orange 6 1";
+		assert_eq!(Map::parse(invalid).err(), Some("Line 3: Expected \"light <x> <y> <color> <radius> <intensity>\", found \"light 1.5 1.5 orange 6 1\"".to_string()));
+		assert_eq!(Map::new(invalid).lights().len(), 0);
+	}
+
+	#[test]
+	fn walls_block_line_of_sight() {
+		let map = Map::new("     \n  R  \n     ");
+		assert!(map.line_of_sight(&Point {x: 0.5, y: 0.5}, &Point {x: 4.5, y: 0.5}));
+		assert!(!map.line_of_sight(&Point {x: 0.5, y: 1.5}, &Point {x: 4.5, y: 1.5}));
 	}
 }
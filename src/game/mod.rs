@@ -1,10 +1,22 @@
+#[cfg(feature = "debug")]
+pub mod debug;
+pub mod exploration;
+pub mod light;
 pub mod map;
 pub mod player;
 
+#[cfg(feature = "debug")]
+use game::debug::DebugMovement;
+use game::exploration::Exploration;
+use game::light::DynamicLight;
+use game::light::Lightmap;
+use game::map::Face;
 use game::map::Tile;
 use game::map::TilePosition;
 use game::map::Map;
 use game::player::Player;
+use utils::color::LightColor;
+use utils::geometry::Point;
 
 /// Represents the state of our game's virtual world
 pub struct Game {
@@ -12,13 +24,41 @@ pub struct Game {
 	pub map: Map,
 
 	/// The player of our virtual world
-	pub player: Player
+	pub player: Player,
+
+	/// The parts of the map the player has already seen
+	pub exploration: Exploration,
+
+	/// The light of the map's baked lights falling onto the walls
+	pub lightmap: Lightmap,
+
+	/// The lights evaluated while rendering (e.g. the map's torches)
+	pub dynamic_lights: Vec<DynamicLight>,
+
+	/// The player's flashlight, switched off initially
+	pub flashlight: DynamicLight,
+
+	/// The movement modes for inspecting the map (noclip and fly mode)
+	#[cfg(feature = "debug")]
+	pub debug_movement: DebugMovement
 }
 
 impl Game {
-	/// Initializes a new game based on a given map and player.
+	/// The height of the player's eyes above the floor, relative to the height of the walls
+	pub const EYE_HEIGHT: f64 = 0.5;
+
+	/// Initializes a new game based on a given map and player. Initially, the player has not seen anything of the map. The light of
+	/// the map's lights is baked into the lightmap.
 	pub fn new(map: Map, player: Player) -> Game {
-		Game {map, player}
+		let exploration = Exploration::new(map.width(), map.height());
+		let lightmap = Lightmap::bake(&map, map.lights());
+		let dynamic_lights = map.dynamic_lights().to_vec();
+		Game {
+			map, player, exploration, lightmap, dynamic_lights,
+			flashlight: DynamicLight::flashlight(),
+			#[cfg(feature = "debug")]
+			debug_movement: DebugMovement::new()
+		}
 	}
 
 	/// Rotates the player's viewing angle with the given angle.
@@ -29,7 +69,8 @@ impl Game {
 		self.player.direction += step;
 	}		
 
-	/// Moves the player by the given distance in its current viewing direction. The player is not moved if it would collide with a wall.
+	/// Moves the player by the given distance in its current viewing direction. The player is not moved if it would collide with a wall,
+	/// unless noclip is enabled (see `DebugMovement`).
 	///
 	/// # Parameters:
 	///		- `distance:		The distance the player should be moved by.
@@ -38,9 +79,64 @@ impl Game {
 		// See: https://en.wikipedia.org/wiki/Trigonometry#Overview	
 		let new_position = self.player.position.add(distance, self.player.direction);
 
-		match self.map.tile(&TilePosition::new(&new_position, &self.player.direction)) {
-			Tile::Empty => {self.player.position = new_position},
-			Tile::Wall(_) => {}
+		match self.map.tile(&TilePosition::containing(&new_position)) {
+			Tile::Wall(_) if !self.ignores_walls() => {},
+			_ => {self.player.position = new_position}
+		}
+	}
+
+	/// The height of the player's eyes above the floor, relative to the height of the walls (0: floor, 1: ceiling). The eyes are at
+	/// half the wall height, unless the player flies (see `DebugMovement`).
+	pub fn eye_height(&self) -> f64 {
+		#[cfg(feature = "debug")] {
+			if self.debug_movement.fly {
+				return self.debug_movement.eye_height;
+			}
+		}
+
+		Game::EYE_HEIGHT
+	}
+
+	/// Moves the flashlight along with the player and lets the dynamic lights flicker. Should be called before rendering a frame.
+	///
+	/// # Parameters:
+	///		- `time`:		The time since the start of the game in seconds
+	pub fn update_lights(&mut self, time: f64) {
+		self.flashlight.light.position = self.player.position.clone();
+		if let Some(ref mut spot) = self.flashlight.spot {
+			spot.direction = self.player.direction;
+		}
+
+		for light in self.dynamic_lights.iter_mut() {
+			light.update(time);
 		}
 	}
+
+	/// Whether a light flickers, which requires redrawing the scene continuously
+	pub fn has_flickering_lights(&self) -> bool {
+		self.dynamic_lights.iter().any(|light| light.is_flickering())
+	}
+
+	/// Determines the light of the baked lights, the dynamic lights and the flashlight falling onto a point of a wall face.
+	///
+	/// # Parameters:
+	///		- `tile`:		The position of the wall tile
+	///		- `face`:		The face of the wall tile
+	///		- `point`:		The point on the face
+	pub fn light_at(&self, tile: &TilePosition, face: Face, point: &Point) -> LightColor {
+		self.dynamic_lights.iter().chain(Some(&self.flashlight))
+			.fold(self.lightmap.light_at(tile, face, point), |light, dynamic_light| light.add(&dynamic_light.light_at(&self.map, point, face)))
+	}
+
+	/// Whether the player can move through walls
+	#[cfg(feature = "debug")]
+	fn ignores_walls(&self) -> bool {
+		self.debug_movement.noclip
+	}
+
+	/// Whether the player can move through walls. Only possible with the debug movement.
+	#[cfg(not(feature = "debug"))]
+	fn ignores_walls(&self) -> bool {
+		false
+	}
 }
\ No newline at end of file
@@ -0,0 +1,43 @@
+//! A ray casting engine rendering a 3D maze from a 2D map.
+//!
+//! The engine renders into any pixel buffer, so it can be used without a window:
+//!
+//! ```
+//! use raycaster::game::Game;
+//! use raycaster::game::map::Map;
+//! use raycaster::game::player::Player;
+//! use raycaster::renderer::Renderer;
+//! use raycaster::renderer::profiler::Profiler;
+//! use raycaster::utils::canvas::Canvas;
+//! use raycaster::utils::geometry::Point;
+//! use raycaster::utils::pixel_format::PixelFormat;
+//!
+//! let game = Game::new(Map::new("RRRR\nR  R\nRRRR"), Player {position: Point {x: 1.5, y: 1.5}, direction: 0.0});
+//! let renderer = Renderer::new(game, 67.0, 100.0, 0.25, Profiler::new(false));
+//!
+//! let (width, height) = (320, 200);
+//! let mut buffer = vec![0u8; width * height * 3];
+//! renderer.render(&mut Canvas::new(&mut buffer, width * 3, width, height, PixelFormat::RGB24));
+//! ```
+//!
+//! Presenting frames in an SDL window and receiving user events (`window::Window`) requires the `sdl` feature, which is enabled by
+//! default. Without it, frames can be presented by the software backend `utils::frame_buffer::FrameBuffer`.
+
+#[cfg(feature = "sdl")]
+extern crate sdl2;
+
+#[cfg(test)]
+extern crate proptest;
+
+// The parameter lists of the modules' doc comments are indented with tabs, which rustdoc would mistake for code examples. The modules
+// are left out while collecting doctests, so only the examples of the crate documentation are tested.
+#[cfg(not(doctest))]
+pub mod demo;
+#[cfg(not(doctest))]
+pub mod game;
+#[cfg(not(doctest))]
+pub mod renderer;
+#[cfg(not(doctest))]
+pub mod utils;
+#[cfg(not(doctest))]
+pub mod window;
@@ -1,89 +1,644 @@
-extern crate sdl2;
+extern crate raycaster;
 
+mod commands;
 mod emscripten;
-mod game;
-mod renderer;
-mod utils;
-mod window;
-
-use game::Game;
-use game::map::Map;
-use game::player::Player;
-use renderer::Renderer;
+
+use raycaster::demo::load_game;
+use raycaster::demo::menus::MenuAction;
+use raycaster::demo::menus::MenuInput;
+use raycaster::demo::menus::Menus;
+use raycaster::demo::menus::Screen;
+use raycaster::demo::settings::Action;
+use raycaster::demo::settings::Settings;
+use raycaster::game::Game;
+use raycaster::renderer::Renderer;
+use raycaster::renderer::debug_view::DebugView;
+use raycaster::renderer::hud::Hud;
+use raycaster::renderer::minimap::Minimap;
+use raycaster::renderer::minimap::Orientation;
+use raycaster::renderer::profiler::FrameTiming;
+use raycaster::renderer::profiler::Profiler;
+use raycaster::utils::config::Config;
+use raycaster::window::ViewSettings;
+use raycaster::window::Window;
+use raycaster::window::config::WindowConfig;
+use raycaster::window::event::ControllerButton;
+use raycaster::window::event::Event;
+use raycaster::window::event::Keycode;
+use raycaster::window::event::MouseButton;
+use raycaster::window::render_scale::Filtering;
+use raycaster::window::render_scale::RenderScale;
+use raycaster::window::render_scale::Upscaling;
+use std::env;
+use std::fs::File;
 use std::process::exit;
 use std::time::Duration;
-use utils::geometry::Point;
-use window::Window;
-use window::event::Event;
-use window::event::Keycode;
+use std::time::Instant;
+
+/// The angle the player is rotated by per logical point of mouse motion (at a mouse sensitivity of 1.0)
+const MOUSE_ROTATION_SPEED: f64 = 0.003;
 
 fn main() {
+	let arguments: Vec<String> = env::args().collect();
+
+	// Load the settings from the configuration file ("--config <path>", "raycaster.cfg" by default) and the command line
+	let config_path = option_value(&arguments, "--config").unwrap_or_else(|| "raycaster.cfg".to_string());
+	let file_config = Config::load(&config_path).unwrap_or_else(|error| fail(&error));
+	let mut config = file_config.clone();
+	for &(option, key) in WindowConfig::OPTIONS.iter() {
+		if let Some(value) = option_value(&arguments, option) {
+			config.set(key, &value);
+		}
+	}
+	let window_config = WindowConfig::from_config(&config).unwrap_or_else(|error| fail(&error));
+
 	// Initialize the graphics and event handling.
-	let mut window = Window::new();
+	let mut window = Window::new(&window_config).unwrap_or_else(|error| fail(&error));
+	println!("Renderer: {}", window.view.backend_description());
+
+	// Keep the timings of all frames if they should be exported on exit ("--frame-timings <path>")
+	let frame_timings_path = option_value(&arguments, "--frame-timings");
+
+	// Initialize the renderer with the built-in map and apply the settings changed in the menu
+	let game = load_game(&None).unwrap_or_else(|error| fail(&error));
+	let mut renderer = Renderer::new(game, 67.0, 100.0, 0.25, Profiler::new(frame_timings_path.is_some()));
+	let mut settings = Settings::load(file_config, &config_path, &mut window.view, &mut renderer).unwrap_or_else(|error| fail(&error));
 
-	// Load the game and place the player within the map.
-	let game = Game::new(
-		Map::new(include_str!("../assets/map.txt")),
-		Player {position: Point {x: 4.5, y: 5.5}, direction: 0.0}
-	);
+	// Load the game on the chosen map and place the player within the map.
+	if settings.map.is_some() {
+		match load_game(&settings.map) {
+			Ok(game) => { renderer.game = game; },
+			Err(error) => { eprintln!("{}, using the built-in map", error); settings.map = None; }
+		}
+	}
 
-	// Initialize the renderer
-	let mut renderer = Renderer::new(game, 1.0, 0.75, 100.0, 0.25);
+	// Start with the title screen. A screen refresh is required after startup.
+	let mut state = State {initial_run: true, frame_timings_path, settings, menus: Menus::new(), started: Instant::now()};
+	state.menus.open(Screen::Title, &window.view, &mut renderer, &state.settings);
 
-	// Require a screen refresh after startup.	
-	let mut initial_run = true;
+	// Run the console commands of a script file ("--exec <path>")
+	if let Some(path) = option_value(&arguments, "--exec") {
+		if let Err(error) = commands::exec_script(&path, &mut window, &mut renderer, &mut state) {
+			eprintln!("{}", error);
+		}
+	}
 
 	// Run main loop on normal UI targets.
     #[cfg(not(target_os = "emscripten"))]
     loop { 
-    	main_loop(&mut window, &mut renderer, &initial_run);
-    	initial_run = false;
+    	main_loop(&mut window, &mut renderer, &mut state);
+    	state.initial_run = false;
     }
 
     // Run main loop on web targets.
     #[cfg(target_os = "emscripten")] {
 	    use emscripten::{emscripten};
 	    emscripten::set_main_loop_callback(|| {
-	    	main_loop(&mut window, &mut renderer, &initial_run);
-	    	initial_run = false;
+	    	main_loop(&mut window, &mut renderer, &mut state);
+	    	state.initial_run = false;
 	    });
 	}    
 }
 
+/// The state of the demo besides the window and the renderer.
+struct State {
+	/// Whether the main loop runs for the first time, which requires a screen refresh
+	initial_run: bool,
+
+	/// The path the frame timings are written to on exit
+	frame_timings_path: Option<String>,
+
+	/// The settings that can be changed in the menu
+	settings: Settings,
+
+	/// The screens of the menu
+	menus: Menus,
+
+	/// The time the demo started, which lets the lights flicker
+	started: Instant
+}
+
+/// Returns the value following an option on the command line (e.g. the path in "--frame-timings <path>").
+///
+/// # Parameters:
+///		- arguments:	The command line arguments
+///		- option:		The name of the option
+fn option_value(arguments: &[String], option: &str) -> Option<String> {
+	arguments.iter().position(|argument| argument == option).and_then(|index| arguments.get(index + 1)).cloned()
+}
+
+/// Prints an error message and exits.
+fn fail(message: &str) -> ! {
+	eprintln!("{}", message);
+	exit(1);
+}
+
 /// The main event handling loop.
-fn main_loop(window: &mut Window, renderer: &mut Renderer, initial_run: &bool) {
-	let mut needs_refresh = *initial_run;
+fn main_loop(window: &mut Window, renderer: &mut Renderer, state: &mut State) {
+	let frame_start = Instant::now();
+	let mut needs_refresh = state.initial_run;
+
+	// Handle all pending UI events
+	for event in window.event_source.poll_events() {
+		match event {
+			Event::Quit => { quit(renderer, &state.frame_timings_path); },
+			Event::Resize => { window.view.resize(); needs_refresh = true; },
+
+			// Pause the game while the window is in the background
+			Event::FocusLost | Event::Minimized => {
+				if !renderer.menu.visible {
+					state.menus.open(Screen::Pause, &window.view, renderer, &state.settings);
+				}
+				needs_refresh = true;
+			},
+			Event::FocusGained | Event::Restored => { needs_refresh = true; },
 
-	// Get pending UI events
-	match window.event_source.poll_next_event() {
-		None => {},
-		Some(Event::Quit) => { exit(0); },
-		Some(Event::Resize) => { needs_refresh = true; }
+			// The next key pressed is bound to an action while a key binding is changed
+			Event::KeyDown(keycode) if state.menus.is_rebinding() => {
+				state.menus.bind_key(keycode, &window.view, renderer, &mut state.settings);
+				needs_refresh = true;
+			},
+
+			// Backquote: Show/hide the console
+			Event::KeyDown(Keycode::Backquote) => {
+				renderer.console.toggle();
+				needs_refresh = true;
+			},
+
+			// The console takes all input while it is shown
+			Event::KeyDown(..) | Event::TextInput(..) | Event::MouseMotion {..} | Event::MouseButtonDown {..} | Event::MouseWheel {..}
+				| Event::ControllerButtonDown(..) if renderer.console.visible => {
+				handle_console_event(&event, window, renderer, state);
+				needs_refresh = true;
+			},
+
+			// The menu takes all input while it is shown
+			Event::KeyDown(..) | Event::MouseMotion {..} | Event::MouseButtonDown {..} | Event::MouseWheel {..} | Event::ControllerButtonDown(..)
+				if renderer.menu.visible => {
+				handle_menu_event(&event, window, renderer, state);
+				needs_refresh = true;
+			},
+
+			// Escape or the controller's start button: Open the pause menu
+			Event::KeyDown(Keycode::Escape) | Event::ControllerButtonDown(ControllerButton::Start) => {
+				state.menus.open(Screen::Pause, &window.view, renderer, &state.settings);
+				needs_refresh = true;
+			},
+
+			// Mouse: Turn the player, unless the scene is frozen or replaced by the automap
+			Event::MouseMotion {relative_x, ..} if !renderer.automap.visible && renderer.debug_view.inspected_ray.is_none() => {
+				renderer.game.rotate_player(relative_x as f64 * MOUSE_ROTATION_SPEED * state.settings.mouse_sensitivity);
+				needs_refresh = true;
+			},
+
+			Event::KeyDown(keycode) => {
+				let handled = handle_render_scale_key(window, &mut renderer.hud, &keycode)
+					|| handle_fullscreen_key(window, &mut renderer.hud, &keycode)
+					|| handle_hud_key(&mut renderer.hud, &keycode)
+					|| handle_profiler_key(&mut renderer.profiler, &keycode)
+					|| handle_debug_view_key(&mut renderer.debug_view, &keycode)
+					|| handle_automap_key(renderer, &keycode)
+					|| handle_minimap_key(&mut renderer.minimap, &keycode)
+					|| handle_flashlight_key(&mut renderer.game, &mut renderer.hud, &keycode);
+				needs_refresh = handled || needs_refresh;
+			},
+			_ => {}
+		}
 	}
 
-	// Handle key presses
-	for keycode in window.event_source.pressed_keycodes() {
+	// Use the mouse for looking around while playing and for choosing items while the menu is shown
+	let paused = renderer.menu.visible || renderer.console.visible;
+	window.event_source.set_mouse_captured(!paused && window.event_source.has_focus());
+
+	// Handle key presses. The game is paused while the menu or the console is shown.
+	let pressed_keycodes = if paused { Vec::new() } else { window.event_source.pressed_keycodes() };
+	for keycode in pressed_keycodes {
 		needs_refresh = true;
 		let movement_speed = 0.2;
 		let rotation_speed = 0.05;
+		let pan_speed = 0.05;
 
-		match keycode {
-			// Arrow up/down: Move player forward/backwards
-			Keycode::Up => { renderer.game.move_player(movement_speed) },
-			Keycode::Down => { renderer.game.move_player(-movement_speed) },
-				
-			// Arrow right/left: Rotate player
-			Keycode::Right => { renderer.game.rotate_player(rotation_speed); },
-			Keycode::Left => { renderer.game.rotate_player(-rotation_speed); },
+		// Arrow keys: Pan the automap while it is shown
+		if renderer.automap.visible {
+			match keycode {
+				Keycode::Up => { renderer.automap.pan(0.0, -pan_speed); },
+				Keycode::Down => { renderer.automap.pan(0.0, pan_speed); },
+				Keycode::Right => { renderer.automap.pan(pan_speed, 0.0); },
+				Keycode::Left => { renderer.automap.pan(-pan_speed, 0.0); },
+				_ => {}
+			}
+			continue;
 		}
+
+		// The scene is frozen while a ray is inspected
+		if renderer.debug_view.inspected_ray.is_some() {
+			continue;
+		}
+
+		// Bound keys (arrow keys by default): Move the player forward/backwards and rotate it
+		let settings = &state.settings;
+		if keycode == settings.binding(Action::MoveForward) { renderer.game.move_player(movement_speed); }
+		else if keycode == settings.binding(Action::MoveBackward) { renderer.game.move_player(-movement_speed); }
+		else if keycode == settings.binding(Action::TurnRight) { renderer.game.rotate_player(rotation_speed); }
+		else if keycode == settings.binding(Action::TurnLeft) { renderer.game.rotate_player(-rotation_speed); }
+
+		// E/Q: Fly up/down (only in fly mode, see the console)
+		#[cfg(feature = "debug")] {
+			let fly_speed = 0.02;
+			match keycode {
+				Keycode::E => { renderer.game.fly_player(fly_speed); },
+				Keycode::Q => { renderer.game.fly_player(-fly_speed); },
+				_ => {}
+			}
+		}
+	}
+
+	// Keep refreshing while messages are shown, so they disappear in time
+	if renderer.hud.has_messages() {
+		renderer.hud.remove_expired_messages();
+		needs_refresh = true;
 	}
 
-	// Refresh screen if needed 
-	if needs_refresh {
-		window.view.draw_canvas({|mut canvas| 
-			renderer.render(&mut canvas)
+	// Keep refreshing while lights flicker, unless the game is paused
+	if !paused && renderer.game.has_flickering_lights() {
+		needs_refresh = true;
+	}
+
+	// Keep refreshing while the performance overlay is shown, so the frame rate is measured continuously
+	if renderer.profiler.visible {
+		needs_refresh = true;
+	}
+
+	// Refresh screen if needed. Minimized windows are not drawn at all.
+	let minimized = window.event_source.is_minimized();
+	if needs_refresh && !minimized {
+		let simulation = frame_start.elapsed();
+		let draw_start = Instant::now();
+		let mut render_timing = Default::default();
+
+		renderer.scale_factor = window.view.frame_scale_factor();
+		renderer.game.update_lights(state.started.elapsed().as_secs_f64());
+		let result = window.view.draw_canvas(|canvas| {
+			render_timing = renderer.render(canvas);
 		});
+
+		match result {
+			Ok(()) => {
+				// Everything besides rendering the frame counts as presenting it
+				let present = draw_start.elapsed().checked_sub(render_timing.ray_casting + render_timing.drawing).unwrap_or_default();
+
+				renderer.profiler.record(frame_start, FrameTiming {
+					simulation, ray_casting: render_timing.ray_casting, drawing: render_timing.drawing, present, ..Default::default()
+				});
+			},
+
+			// The HUD message keeps the screen refreshing, so the next frame is drawn at the restored render scale
+			Err(error) => {
+				eprintln!("{}", error);
+				renderer.hud.show_message(&error);
+			}
+		}
+	}
+
+	// Throttle the main loop while the window is in the background
+	let frame_duration = if window.event_source.has_focus() && !minimized { 1000/60 } else { 100 };
+	std::thread::sleep(Duration::from_millis(frame_duration));
+}
+
+/// Writes the frame timings and terminates the application.
+///
+/// # Parameters:
+///		- renderer:				The renderer whose profiler collected the frame timings
+///		- frame_timings_path:	The path the frame timings should be written to
+fn quit(renderer: &Renderer, frame_timings_path: &Option<String>) -> ! {
+	write_frame_timings(&renderer.profiler, frame_timings_path);
+	exit(0);
+}
+
+/// Navigates the menu with the keyboard, the mouse or a game controller.
+///
+/// # Parameters:
+///		- event:		The event of the keyboard, the mouse or the controller
+///		- window:		The window the menu is shown in
+///		- renderer:		The renderer showing the menu
+///		- state:		The settings changed by the menu and the screens of the menu
+fn handle_menu_event(event: &Event, window: &mut Window, renderer: &mut Renderer, state: &mut State) {
+	let input = match *event {
+		// Arrow keys, D-pad: Select an item and adjust its value
+		Event::KeyDown(Keycode::Up) | Event::ControllerButtonDown(ControllerButton::DPadUp) => Some(MenuInput::Previous),
+		Event::KeyDown(Keycode::Down) | Event::ControllerButtonDown(ControllerButton::DPadDown) => Some(MenuInput::Next),
+		Event::KeyDown(Keycode::Left) | Event::ControllerButtonDown(ControllerButton::DPadLeft) => Some(MenuInput::Decrease),
+		Event::KeyDown(Keycode::Right) | Event::ControllerButtonDown(ControllerButton::DPadRight) => Some(MenuInput::Increase),
+
+		// Return, space, A: Choose the selected item
+		Event::KeyDown(Keycode::Return) | Event::KeyDown(Keycode::Space) | Event::ControllerButtonDown(ControllerButton::A) => Some(MenuInput::Choose),
+
+		// Escape, B, back, start: Return to the previous screen
+		Event::KeyDown(Keycode::Escape) | Event::ControllerButtonDown(ControllerButton::B) | Event::ControllerButtonDown(ControllerButton::Back)
+			| Event::ControllerButtonDown(ControllerButton::Start) => Some(MenuInput::Back),
+
+		// Mouse: Select the item below the cursor, choose it by clicking and adjust it by scrolling. A right click returns.
+		Event::MouseMotion {x, y, ..} => { select_menu_item_at(window, renderer, x, y); None },
+		Event::MouseButtonDown {button: MouseButton::Left, x, y} if select_menu_item_at(window, renderer, x, y) => Some(MenuInput::Choose),
+		Event::MouseButtonDown {button: MouseButton::Right, ..} => Some(MenuInput::Back),
+		Event::MouseWheel {y, ..} if y > 0 => Some(MenuInput::Increase),
+		Event::MouseWheel {y, ..} if y < 0 => Some(MenuInput::Decrease),
+
+		_ => None
+	};
+
+	let action = match input {
+		Some(input) => state.menus.handle(input, &mut window.view, renderer, &mut state.settings),
+		None => return
+	};
+
+	match action {
+		MenuAction::None => {},
+		MenuAction::Resume => { renderer.menu.visible = false; },
+		MenuAction::Quit => { quit(renderer, &state.frame_timings_path); },
+		MenuAction::LoadMap(map) => {
+			match load_game(&map) {
+				Ok(game) => {
+					renderer.game = game;
+					renderer.menu.visible = false;
+					state.settings.map = map;
+					if let Err(error) = state.settings.save(&window.view, renderer) {
+						renderer.hud.show_message(&error);
+					}
+				},
+				Err(error) => { renderer.hud.show_message(&error); }
+			}
+		}
+	}
+}
+
+/// Edits and runs the input line of the console. Escape closes the console.
+///
+/// # Parameters:
+///		- event:		The event of the keyboard
+///		- window:		The window the console is shown in
+///		- renderer:		The renderer showing the console
+///		- state:		The state changed by the commands
+fn handle_console_event(event: &Event, window: &mut Window, renderer: &mut Renderer, state: &mut State) {
+	match *event {
+		Event::TextInput(ref text) => { renderer.console.insert(text); },
+		Event::KeyDown(Keycode::Backspace) => { renderer.console.backspace(); },
+
+		// Return: Run the entered command
+		Event::KeyDown(Keycode::Return) => {
+			if let Some(command) = renderer.console.submit() {
+				commands::execute(&command, window, renderer, state);
+			}
+		},
+
+		// Arrow up/down: Browse the previous commands
+		Event::KeyDown(Keycode::Up) => { renderer.console.history_previous(); },
+		Event::KeyDown(Keycode::Down) => { renderer.console.history_next(); },
+
+		// Tab: Complete the name of a command or variable
+		Event::KeyDown(Keycode::Tab) => { renderer.console.complete(&commands::names()); },
+
+		Event::KeyDown(Keycode::Escape) => { renderer.console.visible = false; },
+		_ => {}
+	}
+}
+
+/// Selects the menu item at a position within the window. Returns whether there is an item.
+///
+/// # Parameters:
+///		- window:		The window the menu is shown in
+///		- renderer:		The renderer showing the menu
+///		- x, y:			The position within the window (e.g. of the mouse cursor)
+fn select_menu_item_at(window: &Window, renderer: &mut Renderer, x: i32, y: i32) -> bool {
+	let (frame_x, frame_y) = window.view.frame_position(x, y);
+	let (width, height) = window.view.frame_size();
+
+	match renderer.menu.item_at(frame_x, frame_y, renderer.overlay_scale(), width as usize, height as usize) {
+		Some(index) => { renderer.menu.selected = index; true },
+		None => false
+	}
+}
+
+/// Adjusts the render scale of the window for a pressed key. Returns whether the render scale was changed.
+///
+/// # Parameters:
+///		- window:		The window whose render scale should be adjusted
+///		- hud:			The HUD used for showing the new render scale
+///		- keycode:		The pressed key
+fn handle_render_scale_key(window: &mut Window, hud: &mut Hud, keycode: &Keycode) -> bool {
+	let mut render_scale = window.view.render_scale().clone();
+
+	match *keycode {
+		// F2: Cycle through the render resolutions
+		Keycode::F2 => { render_scale.resolution = render_scale.resolution.next_preset(); },
+
+		// F3: Toggle between smooth and pixelated upscaling
+		Keycode::F3 => {
+			render_scale.filtering = match render_scale.filtering {
+				Filtering::Linear => Filtering::Nearest,
+				Filtering::Nearest => Filtering::Linear
+			};
+		},
+
+		// F4: Toggle between integer and fractional upscaling
+		Keycode::F4 => {
+			render_scale.upscaling = match render_scale.upscaling {
+				Upscaling::Fractional => Upscaling::Integer,
+				Upscaling::Integer => Upscaling::Fractional
+			};
+		},
+
+		// F5: Toggle the pixelated retro look
+		Keycode::F5 => {
+			render_scale = if render_scale == RenderScale::retro() { RenderScale::native() } else { RenderScale::retro() };
+		},
+
+		_ => { return false; }
+	}
+
+	hud.show_message(&format!("Resolution {}, {} upscaling, {} filtering", render_scale.resolution, render_scale.upscaling, render_scale.filtering));
+	window.view.set_render_scale(render_scale);
+	true
+}
+
+/// Switches the window between fullscreen and windowed mode for a pressed key. Returns whether the key was handled.
+///
+/// # Parameters:
+///		- window:		The window whose fullscreen mode should be changed
+///		- hud:			The HUD used for showing the new mode or why it could not be changed
+///		- keycode:		The pressed key
+fn handle_fullscreen_key(window: &mut Window, hud: &mut Hud, keycode: &Keycode) -> bool {
+	match *keycode {
+		// F11: Toggle fullscreen mode
+		Keycode::F11 => {
+			match window.view.toggle_fullscreen() {
+				Ok(fullscreen) => hud.show_message(&format!("Fullscreen {}", fullscreen)),
+				Err(error) => hud.show_message(&error)
+			}
+		},
+		_ => { return false; }
+	}
+
+	true
+}
+
+/// Shows or hides the performance overlay for a pressed key. Returns whether the overlay was changed.
+///
+/// # Parameters:
+///		- profiler:		The profiler whose overlay should be adjusted
+///		- keycode:		The pressed key
+fn handle_profiler_key(profiler: &mut Profiler, keycode: &Keycode) -> bool {
+	match *keycode {
+		// F6: Show/hide the performance overlay
+		Keycode::F6 => { profiler.visible = !profiler.visible; },
+		_ => { return false; }
+	}
+
+	true
+}
+
+/// Switches the player's flashlight on or off for a pressed key. Returns whether the flashlight was switched.
+///
+/// # Parameters:
+///		- game:			The game whose flashlight should be switched
+///		- hud:			The HUD used for showing whether the flashlight is on
+///		- keycode:		The pressed key
+fn handle_flashlight_key(game: &mut Game, hud: &mut Hud, keycode: &Keycode) -> bool {
+	match *keycode {
+		// F: Switch the flashlight on/off
+		Keycode::F => {
+			game.flashlight.enabled = !game.flashlight.enabled;
+			hud.show_message(&format!("Flashlight {}", if game.flashlight.enabled { "on" } else { "off" }));
+		},
+		_ => { return false; }
+	}
+
+	true
+}
+
+/// Writes the timings of all frames to a CSV file, if a path was given on the command line.
+///
+/// # Parameters:
+///		- profiler:		The profiler that collected the frame timings
+///		- path:			The path of the CSV file
+fn write_frame_timings(profiler: &Profiler, path: &Option<String>) {
+	if let Some(ref path) = *path {
+		let result = File::create(path).and_then(|mut file| profiler.write_csv(&mut file));
+		if let Err(error) = result {
+			eprintln!("Could not write the frame timings to {}: {}", path, error);
+		}
+	}
+}
+
+/// Shows or hides the HUD for a pressed key. Returns whether the HUD was changed.
+///
+/// # Parameters:
+///		- hud:			The HUD that should be adjusted
+///		- keycode:		The pressed key
+fn handle_hud_key(hud: &mut Hud, keycode: &Keycode) -> bool {
+	match *keycode {
+		// H: Show/hide the HUD
+		Keycode::H => { hud.visible = !hud.visible; },
+		_ => { return false; }
+	}
+
+	true
+}
+
+/// Controls the split screen debug view for a pressed key. Returns whether the debug view was changed.
+///
+/// # Parameters:
+///		- debug_view:	The debug view that should be adjusted
+///		- keycode:		The pressed key
+fn handle_debug_view_key(debug_view: &mut DebugView, keycode: &Keycode) -> bool {
+	let inspect_step = 0.05;
+
+	match *keycode {
+		// F1: Show/hide the debug view
+		Keycode::F1 => {
+			debug_view.visible = !debug_view.visible;
+			debug_view.inspected_ray = None;
+		},
+
+		// I: Start/stop inspecting a single ray
+		Keycode::I if debug_view.visible => {
+			debug_view.inspected_ray = match debug_view.inspected_ray {
+				Some(_) => None,
+				None => Some(0.0)
+			};
+		},
+
+		// Arrow left/right: Move the inspected ray
+		Keycode::Left | Keycode::Right if debug_view.inspected_ray.is_some() => {
+			let step = if let Keycode::Left = *keycode { -inspect_step } else { inspect_step };
+			debug_view.inspected_ray = debug_view.inspected_ray.map(|position| (position + step).clamp(-1.0, 1.0));
+		},
+
+		// Brackets: Draw less/more rays
+		Keycode::LeftBracket if debug_view.visible => { debug_view.ray_spacing *= 2; },
+		Keycode::RightBracket if debug_view.visible => { debug_view.ray_spacing = (debug_view.ray_spacing / 2).max(1); },
+
+		_ => { return false; }
+	}
+
+	true
+}
+
+/// Shows, hides and zooms the automap for a pressed key. Returns whether the automap was changed.
+///
+/// # Parameters:
+///		- renderer:		The renderer whose automap should be adjusted
+///		- keycode:		The pressed key
+fn handle_automap_key(renderer: &mut Renderer, keycode: &Keycode) -> bool {
+	let automap = &mut renderer.automap;
+
+	match *keycode {
+		// Tab: Show/hide the automap
+		Keycode::Tab => {
+			automap.visible = !automap.visible;
+			automap.recenter();
+		},
+
+		// Home: Center the automap on the player
+		Keycode::Home if automap.visible => { automap.recenter(); },
+
+		// Page up/down: Zoom in/out
+		Keycode::PageUp if automap.visible => { automap.zoom(1.25); },
+		Keycode::PageDown if automap.visible => { automap.zoom(0.8); },
+
+		_ => { return false; }
+	}
+
+	true
+}
+
+/// Adjusts the minimap for a pressed key. Returns whether the minimap was changed.
+///
+/// # Parameters:
+///		- minimap:		The minimap that should be adjusted
+///		- keycode:		The pressed key
+fn handle_minimap_key(minimap: &mut Minimap, keycode: &Keycode) -> bool {
+	match *keycode {
+		// M: Show/hide the minimap
+		Keycode::M => { minimap.visible = !minimap.visible; },
+
+		// N: Toggle between a north-up minimap and a minimap rotating with the player
+		Keycode::N => {
+			minimap.orientation = match minimap.orientation {
+				Orientation::NorthUp => Orientation::RotateWithPlayer,
+				Orientation::RotateWithPlayer => Orientation::NorthUp
+			};
+		},
+
+		// C: Move the minimap to the next corner
+		Keycode::C => { minimap.corner = minimap.corner.next(); },
+
+		// Page up/down: Zoom in/out
+		Keycode::PageUp => { minimap.zoom(1.25); },
+		Keycode::PageDown => { minimap.zoom(0.8); },
+
+		_ => { return false; }
 	}
 
-	std::thread::sleep(Duration::from_millis(1000/60));
+	true
 }
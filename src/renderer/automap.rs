@@ -0,0 +1,127 @@
+use game::Game;
+use game::map::Face;
+use game::map::Tile;
+use game::map::TilePosition;
+use renderer::map_frame::MapFrame;
+use utils::canvas::Canvas;
+use utils::color::RGBColor;
+use utils::geometry::Point;
+
+/// A full screen top-down view of the parts of the map the player has already seen. Everything else is hidden.
+pub struct Automap {
+	/// Whether the automap is shown instead of the 3D scene
+	pub visible: bool,
+
+	/// The offset of the automap's center from the player's position (in tiles)
+	pub pan: Point,
+
+	/// The number of tiles visible between the center and the closest border of the automap (i.e. the zoom level)
+	pub radius: f64
+}
+
+impl Automap {
+	/// The smallest and largest number of tiles that can be visible between the center and the closest border.
+	const MIN_RADIUS: f64 = 2.0;
+	const MAX_RADIUS: f64 = 128.0;
+
+	/// The width of the highlighted border of a seen wall face (relative to a tile)
+	const FACE_WIDTH: f64 = 0.15;
+
+	/// Creates a hidden automap centered on the player.
+	pub fn new() -> Automap {
+		Automap {visible: false, pan: Point {x: 0.0, y: 0.0}, radius: 16.0}
+	}
+
+	/// Moves the automap's center. The distance is relative to the zoom level, so panning feels the same on each zoom level.
+	///
+	/// # Parameters:
+	///		- delta_x, delta_y:		The distance to move the center by (1.0: the distance between the center and the closest border)
+	pub fn pan(&mut self, delta_x: f64, delta_y: f64) {
+		self.pan = Point {x: self.pan.x + delta_x * self.radius, y: self.pan.y + delta_y * self.radius};
+	}
+
+	/// Centers the automap on the player.
+	pub fn recenter(&mut self) {
+		self.pan = Point {x: 0.0, y: 0.0};
+	}
+
+	/// Zooms the automap by the given factor (> 1: zoom in, < 1: zoom out).
+	pub fn zoom(&mut self, factor: f64) {
+		self.radius = (self.radius / factor).clamp(Automap::MIN_RADIUS, Automap::MAX_RADIUS);
+	}
+
+	/// Draws the automap onto the entire canvas.
+	///
+	/// # Parameters:
+	///		- game:		The game whose explored map and player should be drawn
+	///		- canvas:	The canvas that should be drawn to.
+	pub fn draw(&self, game: &Game, canvas: &mut Canvas) {
+		let width = canvas.width() as isize;
+		let height = canvas.height() as isize;
+		let scale = (width.min(height) as f64 / 2.0) / self.radius;
+		let center = Point {x: game.player.position.x + self.pan.x, y: game.player.position.y + self.pan.y};
+		let frame = MapFrame::new(0, 0, width, height, center, scale, 0.0);
+
+		self.draw_tiles(game, &frame, canvas);
+		self.draw_player(game, &frame, canvas);
+	}
+
+	/// Draws all seen floors and walls. Wall faces the player has seen are highlighted.
+	fn draw_tiles(&self, game: &Game, frame: &MapFrame, canvas: &mut Canvas) {
+		let floor = RGBColor::dark_gray();
+
+		for y in frame.rows() {
+			for x in frame.columns() {
+				if !canvas.is_drawable(x, y) {
+					continue;
+				}
+
+				let point = frame.map_point(x, y);
+				let position = TilePosition::containing(&point);
+				let exploration = &game.exploration;
+
+				let color = match game.map.tile(&position) {
+					Tile::Empty if exploration.is_tile_seen(&position) => floor.clone(),
+					Tile::Wall(color) if exploration.is_any_face_seen(&position) => {
+						let on_seen_face = Face::ALL.iter().any(|face|
+							exploration.is_face_seen(&position, *face) && Automap::is_on_face(&point, *face)
+						);
+
+						if on_seen_face { color } else { color.adjust_light_intensity(0.5) }
+					},
+					_ => RGBColor::black()
+				};
+
+				canvas.draw_pixel(x as usize, y as usize, &color);
+			}
+		}
+	}
+
+	/// Draws the player's position and viewing direction.
+	fn draw_player(&self, game: &Game, frame: &MapFrame, canvas: &mut Canvas) {
+		let (x, y) = frame.canvas_point(&game.player.position);
+		let nose = game.player.position.add(1.0, game.player.direction);
+
+		canvas.draw_line((x, y), frame.canvas_point(&nose), &RGBColor::white());
+		canvas.fill_rect(x - 2, y - 2, 5, 5, &RGBColor::white());
+	}
+
+	/// Whether a point lies on the highlighted border of a tile's face.
+	fn is_on_face(point: &Point, face: Face) -> bool {
+		let x = point.x - point.x.floor();
+		let y = point.y - point.y.floor();
+
+		match face {
+			Face::North => y < Automap::FACE_WIDTH,
+			Face::East => x > 1.0 - Automap::FACE_WIDTH,
+			Face::South => y > 1.0 - Automap::FACE_WIDTH,
+			Face::West => x < Automap::FACE_WIDTH
+		}
+	}
+}
+
+impl Default for Automap {
+	fn default() -> Automap {
+		Automap::new()
+	}
+}
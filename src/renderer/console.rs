@@ -0,0 +1,232 @@
+use std::collections::VecDeque;
+use utils::canvas::Canvas;
+use utils::color::RGBColor;
+use utils::font;
+
+/// A drop-down console drawn over the upper half of the screen. Keeps the text typed into the input line, the output of previous
+/// commands and the history of entered commands. What the commands do is defined by the application.
+pub struct Console {
+	/// Whether the console is drawn
+	pub visible: bool,
+
+	/// The text of the input line
+	pub input: String,
+
+	/// The lines printed to the console, the most recent line last
+	output: VecDeque<String>,
+
+	/// The commands entered so far, the most recent command last
+	history: Vec<String>,
+
+	/// The index of the history entry shown in the input line while browsing the history
+	history_index: Option<usize>
+}
+
+impl Console {
+	/// The maximum number of output lines kept
+	const MAX_OUTPUT_LINES: usize = 200;
+
+	/// The opacity of the background behind the console
+	const BACKGROUND_ALPHA: u8 = 200;
+
+	/// The margin between the text and the border of the console (in font pixels)
+	const MARGIN: usize = 2;
+
+	/// The characters written in front of the input line and of entered commands
+	const PROMPT: &'static str = "] ";
+
+	/// Creates a hidden, empty console.
+	pub fn new() -> Console {
+		Console {visible: false, input: String::new(), output: VecDeque::new(), history: Vec::new(), history_index: None}
+	}
+
+	/// Shows or hides the console.
+	pub fn toggle(&mut self) {
+		self.visible = !self.visible;
+	}
+
+	/// Prints a text to the console. Texts with several lines are split into several output lines.
+	pub fn print(&mut self, text: &str) {
+		for line in text.lines() {
+			if self.output.len() == Console::MAX_OUTPUT_LINES {
+				self.output.pop_front();
+			}
+			self.output.push_back(line.to_string());
+		}
+	}
+
+	/// Removes all output lines.
+	pub fn clear(&mut self) {
+		self.output.clear();
+	}
+
+	/// The lines printed to the console, the most recent line last
+	pub fn output(&self) -> &VecDeque<String> {
+		&self.output
+	}
+
+	/// Appends entered text to the input line. Control characters and the backquote toggling the console are ignored.
+	pub fn insert(&mut self, text: &str) {
+		self.input.extend(text.chars().filter(|character| !character.is_control() && *character != '`' && *character != '~'));
+	}
+
+	/// Removes the last character of the input line.
+	pub fn backspace(&mut self) {
+		self.input.pop();
+	}
+
+	/// Takes the command of the input line and clears it. The command is echoed to the output and added to the history. Returns
+	/// `None` if the input line is empty.
+	pub fn submit(&mut self) -> Option<String> {
+		let command = self.input.trim().to_string();
+		self.input.clear();
+		self.history_index = None;
+
+		if command.is_empty() {
+			return None;
+		}
+
+		self.print(&format!("{}{}", Console::PROMPT, command));
+		if self.history.last() != Some(&command) {
+			self.history.push(command.clone());
+		}
+		Some(command)
+	}
+
+	/// Replaces the input line with the previous command of the history.
+	pub fn history_previous(&mut self) {
+		if self.history.is_empty() {
+			return;
+		}
+
+		let index = self.history_index.map_or(self.history.len() - 1, |index| index.saturating_sub(1));
+		self.history_index = Some(index);
+		self.input = self.history[index].clone();
+	}
+
+	/// Replaces the input line with the next command of the history. Clears the input line after the most recent command.
+	pub fn history_next(&mut self) {
+		match self.history_index {
+			Some(index) if index + 1 < self.history.len() => {
+				self.history_index = Some(index + 1);
+				self.input = self.history[index + 1].clone();
+			},
+			Some(_) => {
+				self.history_index = None;
+				self.input.clear();
+			},
+			None => {}
+		}
+	}
+
+	/// Completes the first word of the input line. A single matching candidate is completed entirely, otherwise the input is
+	/// extended to the longest common prefix and the matching candidates are printed.
+	///
+	/// # Parameters:
+	///		- candidates:	The names that can be entered (e.g. of all commands and variables)
+	pub fn complete(&mut self, candidates: &[&str]) {
+		if self.input.contains(' ') {
+			return;
+		}
+
+		let mut matches: Vec<&str> = candidates.iter().cloned().filter(|candidate| candidate.starts_with(self.input.as_str())).collect();
+		matches.sort();
+		matches.dedup();
+
+		match matches.len() {
+			0 => {},
+			1 => { self.input = format!("{} ", matches[0]); },
+			_ => {
+				let mut prefix = matches[0].to_string();
+				for candidate in matches.iter() {
+					while !candidate.starts_with(prefix.as_str()) {
+						prefix.pop();
+					}
+				}
+				self.input = prefix;
+				self.print(&matches.join("  "));
+			}
+		}
+	}
+
+	/// Draws the console over the upper half of a canvas. The most recent output lines are shown above the input line.
+	///
+	/// # Parameters:
+	///		- scale:	The size of a font pixel in canvas pixels
+	///		- canvas:	The canvas that should be drawn to.
+	pub fn draw(&self, scale: usize, canvas: &mut Canvas) {
+		if !self.visible {
+			return;
+		}
+
+		let scale = scale.max(1);
+		let width = canvas.width();
+		let height = canvas.height() / 2;
+		let margin = (Console::MARGIN * scale) as isize;
+		let line_height = (font::LINE_HEIGHT * scale) as isize;
+
+		canvas.blend_rect(0, 0, width, height, &RGBColor::black(), Console::BACKGROUND_ALPHA);
+		canvas.fill_rect(0, height as isize, width, scale, &RGBColor::light_gray());
+
+		let input_top = height as isize - margin - line_height;
+		canvas.draw_text(margin, input_top, &format!("{}{}_", Console::PROMPT, self.input), &RGBColor::yellow(), scale);
+
+		let mut top = input_top;
+		for line in self.output.iter().rev() {
+			top -= line_height;
+			if top < 0 {
+				break;
+			}
+			canvas.draw_text(margin, top, line, &RGBColor::white(), scale);
+		}
+	}
+}
+
+impl Default for Console {
+	fn default() -> Console {
+		Console::new()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn browses_history() {
+		let mut console = Console::new();
+		for command in ["noclip", "teleport 2 3", "teleport 2 3", ""].iter() {
+			console.input = command.to_string();
+			console.submit();
+		}
+
+		console.history_previous();
+		assert_eq!(console.input, "teleport 2 3");
+		console.history_previous();
+		console.history_previous();
+		assert_eq!(console.input, "noclip");
+		console.history_next();
+		assert_eq!(console.input, "teleport 2 3");
+		console.history_next();
+		assert_eq!(console.input, "");
+	}
+
+	#[test]
+	fn completes_names() {
+		let mut console = Console::new();
+		let candidates = ["map", "minimum_light", "noclip", "screenshot"];
+
+		console.input = "n".to_string();
+		console.complete(&candidates);
+		assert_eq!(console.input, "noclip ");
+
+		console.input = "m".to_string();
+		console.complete(&candidates);
+		assert_eq!(console.input, "m");
+		assert_eq!(console.output().back().map(|line| line.as_str()), Some("map  minimum_light"));
+
+		console.input = "x".to_string();
+		console.complete(&candidates);
+		assert_eq!(console.input, "x");
+	}
+}
@@ -0,0 +1,226 @@
+use game::Game;
+use game::map::Face;
+use game::map::Tile;
+use game::map::TilePosition;
+use renderer::Projection;
+use renderer::Renderer;
+use renderer::map_frame::MapFrame;
+use renderer::ray::Ray;
+use std::fmt::Write;
+use utils::canvas::Canvas;
+use utils::color::RGBColor;
+use utils::font;
+use utils::geometry::Angle;
+use utils::geometry::Point;
+
+/// An educational view that shows how the 3D scene is created: the left half of the canvas shows the 3D scene, the right half
+/// a top-down map with the rays that were casted for the scene, the grid lines they crossed and the wall faces they hit.
+pub struct DebugView {
+	/// Whether the debug view is shown instead of the 3D scene
+	pub visible: bool,
+
+	/// Only every n-th ray of the scene is drawn on the map
+	pub ray_spacing: usize,
+
+	/// The position on the camera plane of the ray being inspected (-1: left border, 1: right border). While a ray is inspected, only
+	/// this ray is drawn and the scene should be frozen.
+	pub inspected_ray: Option<f64>,
+
+	/// The number of tiles visible between the player and the closest border of the map
+	pub radius: f64
+}
+
+/// The steps of a ray traced through the map until it hit a wall.
+pub struct RayTrace {
+	/// The angle of the ray relative to the player's viewing direction
+	pub relative_angle: Angle,
+
+	/// The grid line intersections visited by the ray and the face of the tile entered at each intersection
+	pub steps: Vec<(Point, Face)>,
+
+	/// The wall tile the ray hit and the face it was hit on
+	pub hit: Option<(TilePosition, Face)>,
+
+	/// The length of the ray
+	pub length: f64,
+
+	/// The distance of the hit perpendicular to the camera plane, which determines the wall's height
+	pub distance: f64
+}
+
+impl DebugView {
+	/// Creates a hidden debug view drawing every 16th ray.
+	pub fn new() -> DebugView {
+		DebugView {visible: false, ray_spacing: 16, inspected_ray: None, radius: 10.0}
+	}
+
+	/// Draws the 3D scene and the map with the casted rays side by side.
+	///
+	/// # Parameters:
+	///		- renderer:		The renderer used for drawing the 3D scene
+	///		- canvas:		The canvas that should be drawn to.
+	pub fn draw(&self, renderer: &Renderer, canvas: &mut Canvas) {
+		let split = canvas.width() / 2;
+		let projection = Projection::new(renderer.field_of_view, split);
+
+		// The 3D scene on the left half
+		{
+			let mut scene = canvas.sub_canvas(0..split);
+			renderer.render_scene(&mut scene);
+
+			if let Some(camera_position) = self.inspected_ray {
+				let column = projection.column(camera_position) as isize;
+				scene.draw_line((column, 0), (column, scene.height() as isize - 1), &RGBColor::white());
+			}
+		}
+
+		// The map with the rays on the right half
+		let mut map = canvas.sub_canvas(split..canvas.width());
+		let scale = (map.width().min(map.height()) as f64 / 2.0) / self.radius;
+		let frame = MapFrame::new(0, 0, map.width() as isize, map.height() as isize, renderer.game.player.position.clone(), scale, 0.0);
+
+		self.draw_tiles(&renderer.game, &frame, &mut map);
+
+		let relative_angles: Vec<Angle> = match self.inspected_ray {
+			Some(camera_position) => vec![projection.camera_angle(camera_position)],
+			None => (0..split).step_by(self.ray_spacing.max(1)).map(|column| projection.ray_angle(column)).collect()
+		};
+
+		for relative_angle in relative_angles {
+			let trace = RayTrace::new(&renderer.game, relative_angle);
+			self.draw_trace(&renderer.game, &trace, &frame, &mut map);
+		}
+
+		let (x, y) = frame.canvas_point(&renderer.game.player.position);
+		map.fill_rect(x - 2, y - 2, 5, 5, &RGBColor::white());
+
+		// The steps of the inspected ray in the upper left corner of the map
+		if let Some(report) = self.inspection_report(renderer) {
+			self.draw_report(&report, (renderer.overlay_scale() / 2).max(1), &mut map);
+		}
+	}
+
+	/// Describes the steps of the inspected ray in a human readable form.
+	///
+	/// # Parameters:
+	///		- renderer:		The renderer whose scene is inspected
+	pub fn inspection_report(&self, renderer: &Renderer) -> Option<String> {
+		let camera_position = self.inspected_ray?;
+		let relative_angle = Projection::new(renderer.field_of_view, 1).camera_angle(camera_position);
+		let trace = RayTrace::new(&renderer.game, relative_angle);
+		let mut report = String::new();
+
+		writeln!(report, "Ray at camera position {:.3} ({:.2} deg)", camera_position, relative_angle.to_degrees()).unwrap();
+		for (index, &(ref point, face)) in trace.steps.iter().enumerate() {
+			writeln!(report, "Step {}: ({:.3}, {:.3}), entered {:?} face", index + 1, point.x, point.y, face).unwrap();
+		}
+
+		match trace.hit {
+			Some((ref tile, face)) => writeln!(report, "Hit: {:?} face of tile ({}, {})", face, tile.x, tile.y).unwrap(),
+			None => writeln!(report, "Hit: no wall").unwrap()
+		}
+		writeln!(report, "Length: {:.3}, distance: {:.3}", trace.length, trace.distance).unwrap();
+
+		Some(report)
+	}
+
+	/// Draws the lines of an inspection report on a dark background. Lines that don't fit on the canvas are left out.
+	///
+	/// # Parameters:
+	///		- report:		The report of the inspected ray
+	///		- scale:		The size of a font pixel in canvas pixels
+	///		- canvas:		The canvas of the map
+	fn draw_report(&self, report: &str, scale: usize, canvas: &mut Canvas) {
+		let margin = (2 * scale) as isize;
+		let line_height = font::LINE_HEIGHT * scale;
+		let line_count = report.lines().count().min(canvas.height().saturating_sub(2 * margin as usize) / line_height);
+		let width = report.lines().map(|line| Canvas::text_size(line, scale).0).max().unwrap_or(0);
+
+		canvas.blend_rect(0, 0, width + 2 * margin as usize, line_count * line_height + 2 * margin as usize, &RGBColor::black(), 160);
+		for (index, line) in report.lines().take(line_count).enumerate() {
+			canvas.draw_text(margin, margin + (index * line_height) as isize, line, &RGBColor::white(), scale);
+		}
+	}
+
+	/// Draws the map tiles and the grid lines between them.
+	fn draw_tiles(&self, game: &Game, frame: &MapFrame, canvas: &mut Canvas) {
+		let background = RGBColor::dark_gray().adjust_light_intensity(0.5);
+		let grid = RGBColor::dark_gray();
+
+		for y in frame.rows() {
+			for x in frame.columns() {
+				let point = frame.map_point(x, y);
+				let is_grid_line = ((point.x - point.x.round()).abs() * frame.scale() < 0.5) || ((point.y - point.y.round()).abs() * frame.scale() < 0.5);
+
+				let color = match game.map.tile(&TilePosition::containing(&point)) {
+					Tile::Wall(color) => color,
+					Tile::Empty if is_grid_line => grid.clone(),
+					Tile::Empty => background.clone()
+				};
+
+				canvas.draw_pixel(x as usize, y as usize, &color);
+			}
+		}
+	}
+
+	/// Draws a traced ray with the grid line intersections it visited and the wall face it hit.
+	/// Intersections with vertical grid lines are drawn yellow, intersections with horizontal grid lines green.
+	fn draw_trace(&self, game: &Game, trace: &RayTrace, frame: &MapFrame, canvas: &mut Canvas) {
+		let end = game.player.position.add(trace.length, game.player.direction + trace.relative_angle);
+		canvas.draw_line(frame.canvas_point(&game.player.position), frame.canvas_point(&end), &RGBColor::light_gray());
+
+		for &(ref point, face) in trace.steps.iter() {
+			let color = match face {
+				Face::East | Face::West => RGBColor::yellow(),
+				Face::North | Face::South => RGBColor::green()
+			};
+
+			let (x, y) = frame.canvas_point(point);
+			canvas.fill_rect(x - 1, y - 1, 3, 3, &color);
+		}
+
+		if let Some((ref tile, face)) = trace.hit {
+			let (from, to) = RayTrace::face_edge(tile, face);
+			canvas.draw_line(frame.canvas_point(&from), frame.canvas_point(&to), &RGBColor::white());
+		}
+	}
+}
+
+impl Default for DebugView {
+	fn default() -> DebugView {
+		DebugView::new()
+	}
+}
+
+impl RayTrace {
+	/// Traces a ray from the player's position until it hits a wall or leaves the map.
+	///
+	/// # Parameters:
+	///		- game:				The game whose map the ray is traced through
+	///		- relative_angle:	The angle of the ray relative to the player's viewing direction
+	pub fn new(game: &Game, relative_angle: Angle) -> RayTrace {
+		let mut ray = Ray::new(game.player.position.clone(), game.player.direction + relative_angle);
+		let mut steps = Vec::new();
+
+		// Trace the ray exactly like the renderer casts it, recording every grid line it crossed
+		let hit = ray.cast(&game.map, |ray| steps.extend(ray.face.map(|face| (ray.end.clone(), face))))
+			.and_then(|_| ray.face.map(|face| (ray.tile.clone(), face)));
+		if let Some((_, face)) = hit {
+			steps.push((ray.end.clone(), face));
+		}
+
+		RayTrace {relative_angle, steps, hit, length: ray.length, distance: ray.length * relative_angle.cos()}
+	}
+
+	/// Determines the end points of the edge of a tile's face.
+	fn face_edge(tile: &TilePosition, face: Face) -> (Point, Point) {
+		let (x, y) = (tile.x as f64, tile.y as f64);
+
+		match face {
+			Face::North => (Point {x, y}, Point {x: x + 1.0, y}),
+			Face::East => (Point {x: x + 1.0, y}, Point {x: x + 1.0, y: y + 1.0}),
+			Face::South => (Point {x, y: y + 1.0}, Point {x: x + 1.0, y: y + 1.0}),
+			Face::West => (Point {x, y}, Point {x, y: y + 1.0})
+		}
+	}
+}
@@ -0,0 +1,140 @@
+use std::fmt;
+use utils::color::RGBColor;
+
+/// Fades the scene to a color with increasing distance from the camera, e.g. for misty or bright outdoor atmospheres. The fog is
+/// applied to walls, floors and ceilings alike, using their distance from the camera plane.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Fog {
+	/// Whether the fog is applied
+	pub enabled: bool,
+
+	/// The color everything fades to
+	pub color: RGBColor,
+
+	/// The distance where the fog begins
+	pub start: f64,
+
+	/// The distance where the fog is complete (linear curve) or almost complete (exponential curves: 95 %)
+	pub end: f64,
+
+	/// How the fog thickens between the start and the end
+	pub curve: FogCurve
+}
+
+/// The curves describing how fog thickens with the distance.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FogCurve {
+	/// The fog thickens evenly
+	Linear,
+
+	/// The fog thickens quickly after its start and approaches its color slowly
+	Exponential,
+
+	/// The fog stays thin after its start, then thickens quickly
+	ExponentialSquared
+}
+
+impl Fog {
+	/// The exponent reached at the end distance by the exponential curves. Leaves 5 % of the original color (`e^-3`).
+	const END_EXPONENT: f64 = 3.0;
+
+	/// Creates disabled, light gray fog between a distance of 2 and 20 tiles.
+	pub fn new() -> Fog {
+		Fog {enabled: false, color: RGBColor::light_gray(), start: 2.0, end: 20.0, curve: FogCurve::Linear}
+	}
+
+	/// Determines how much of a color is covered by fog at a distance (0: no fog, 1: only fog).
+	///
+	/// # Parameters:
+	///		- distance:		The distance from the camera plane
+	pub fn thickness(&self, distance: f64) -> f64 {
+		if !self.enabled || distance <= self.start {
+			return 0.0;
+		}
+
+		// The position between the start and the end of the fog (0: start, 1: end)
+		let position = (distance - self.start) / (self.end - self.start).max(1e-6);
+
+		let thickness = match self.curve {
+			FogCurve::Linear => position,
+			FogCurve::Exponential => 1.0 - (-Fog::END_EXPONENT * position).exp(),
+			FogCurve::ExponentialSquared => 1.0 - (-Fog::END_EXPONENT * position * position).exp()
+		};
+		thickness.clamp(0.0, 1.0)
+	}
+
+	/// Covers a color by fog according to its distance.
+	///
+	/// # Parameters:
+	///		- color:		The color of something seen at the distance
+	///		- distance:		The distance from the camera plane
+	pub fn apply(&self, color: &RGBColor, distance: f64) -> RGBColor {
+		match (self.thickness(distance) * 255.0).round() as u8 {
+			0 => color.clone(),
+			alpha => color.blend(&self.color, alpha)
+		}
+	}
+}
+
+impl Default for Fog {
+	fn default() -> Fog {
+		Fog::new()
+	}
+}
+
+impl FogCurve {
+	/// Parses the name of a curve, as written by `Display`.
+	pub fn parse(name: &str) -> Option<FogCurve> {
+		match name {
+			"linear" => Some(FogCurve::Linear),
+			"exponential" => Some(FogCurve::Exponential),
+			"exponential_squared" => Some(FogCurve::ExponentialSquared),
+			_ => None
+		}
+	}
+}
+
+impl fmt::Display for FogCurve {
+	fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		match *self {
+			FogCurve::Linear => write!(formatter, "linear"),
+			FogCurve::Exponential => write!(formatter, "exponential"),
+			FogCurve::ExponentialSquared => write!(formatter, "exponential_squared")
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn thickens_between_start_and_end() {
+		let mut fog = Fog {enabled: true, color: RGBColor::white(), start: 2.0, end: 12.0, curve: FogCurve::Linear};
+		assert_eq!(fog.thickness(1.0), 0.0);
+		assert_eq!(fog.thickness(7.0), 0.5);
+		assert_eq!(fog.thickness(20.0), 1.0);
+		assert_eq!(fog.apply(&RGBColor::black(), 20.0), RGBColor::white());
+
+		for &curve in [FogCurve::Exponential, FogCurve::ExponentialSquared].iter() {
+			fog.curve = curve;
+			assert_eq!(fog.thickness(2.0), 0.0);
+			assert!((fog.thickness(12.0) - 0.95).abs() < 0.01, "{}", curve);
+			assert!(fog.thickness(7.0) < fog.thickness(8.0));
+		}
+
+		// The squared curve stays thinner near the start
+		assert!(fog.thickness(4.0) < Fog {curve: FogCurve::Exponential, ..fog.clone()}.thickness(4.0));
+
+		fog.enabled = false;
+		assert_eq!(fog.apply(&RGBColor::black(), 20.0), RGBColor::black());
+	}
+
+	#[test]
+	fn parses_curve_names() {
+		for &curve in [FogCurve::Linear, FogCurve::Exponential, FogCurve::ExponentialSquared].iter() {
+			assert_eq!(FogCurve::parse(&curve.to_string()), Some(curve));
+		}
+		assert_eq!(FogCurve::parse("cubic"), None);
+	}
+}
@@ -0,0 +1,154 @@
+use game::Game;
+use renderer::minimap::Corner;
+use std::f64::consts::PI;
+use std::time::Duration;
+use std::time::Instant;
+use utils::canvas::Canvas;
+use utils::color::RGBColor;
+use utils::image::Image;
+
+/// The head-up display: a set of widgets drawn on top of the 3D scene.
+pub struct Hud {
+	/// Whether the HUD is drawn
+	pub visible: bool,
+
+	/// The widgets shown on the HUD
+	pub widgets: Vec<Widget>,
+
+	/// How long a message is shown
+	pub message_duration: Duration,
+
+	/// The messages currently shown and the time they were posted
+	messages: Vec<(String, Instant)>,
+
+	/// The image of the crosshair
+	crosshair: Image
+}
+
+/// The widgets that can be shown on the HUD.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Widget {
+	/// The player's position on the map
+	Position(Corner),
+
+	/// The player's viewing direction in degrees and as compass direction
+	Facing(Corner),
+
+	/// The most recent messages
+	Messages(Corner),
+
+	/// A crosshair at the center of the screen
+	Crosshair
+}
+
+impl Hud {
+	/// The margin between the widgets and the border of the canvas (in font pixels)
+	const MARGIN: usize = 4;
+
+	/// The opacity of the background behind texts
+	const BACKGROUND_ALPHA: u8 = 128;
+
+	/// Creates a visible HUD with the default widgets.
+	pub fn new() -> Hud {
+		let widgets = vec![Widget::Position(Corner::TopLeft), Widget::Facing(Corner::TopLeft), Widget::Messages(Corner::BottomLeft), Widget::Crosshair];
+		let crosshair = Image::from_fn(9, 9, |x, y| {
+			let on_cross = (x == 4 || y == 4) && !(x == 4 && y == 4);
+			(RGBColor::white(), if on_cross { 160 } else { 0 })
+		});
+
+		Hud {visible: true, widgets, message_duration: Duration::from_secs(3), messages: Vec::new(), crosshair}
+	}
+
+	/// Shows a message on the HUD for a while.
+	pub fn show_message(&mut self, message: &str) {
+		self.messages.push((message.to_string(), Instant::now()));
+	}
+
+	/// Whether any messages are shown. The HUD has to be redrawn regularly while messages are shown, so they disappear in time.
+	pub fn has_messages(&self) -> bool {
+		!self.messages.is_empty()
+	}
+
+	/// Removes all messages that have been shown long enough.
+	pub fn remove_expired_messages(&mut self) {
+		let message_duration = self.message_duration;
+		self.messages.retain(|&(_, posted)| posted.elapsed() < message_duration);
+	}
+
+	/// Draws the HUD on top of a canvas.
+	///
+	/// # Parameters:
+	///		- game:		The game whose state should be shown
+	///		- scale:	The size of a font pixel in canvas pixels
+	///		- canvas:	The canvas that should be drawn to.
+	pub fn draw(&self, game: &Game, scale: usize, canvas: &mut Canvas) {
+		if !self.visible {
+			return;
+		}
+
+		let margin = (Hud::MARGIN * scale) as isize;
+
+		for corner in [Corner::TopLeft, Corner::TopRight, Corner::BottomRight, Corner::BottomLeft].iter() {
+			let texts: Vec<String> = self.widgets.iter().filter_map(|widget| self.text(widget, game, *corner)).collect();
+			if texts.is_empty() {
+				continue;
+			}
+
+			let text = texts.join("\n");
+			let (width, height) = Canvas::text_size(&text, scale);
+			let (width, height) = (width as isize, height as isize);
+
+			let x = match *corner {
+				Corner::TopLeft | Corner::BottomLeft => margin,
+				Corner::TopRight | Corner::BottomRight => canvas.width() as isize - width - margin
+			};
+			let y = match *corner {
+				Corner::TopLeft | Corner::TopRight => margin,
+				Corner::BottomLeft | Corner::BottomRight => canvas.height() as isize - height - margin
+			};
+
+			let padding = scale as isize * 2;
+			canvas.blend_rect(x - padding, y - padding, (width + 2 * padding) as usize, (height + 2 * padding) as usize, &RGBColor::black(), Hud::BACKGROUND_ALPHA);
+			canvas.draw_text(x, y, &text, &RGBColor::white(), scale);
+		}
+
+		if self.widgets.contains(&Widget::Crosshair) {
+			let x = (canvas.width() as isize - (self.crosshair.width() * scale) as isize) / 2;
+			let y = (canvas.height() as isize - (self.crosshair.height() * scale) as isize) / 2;
+			canvas.draw_image(x, y, &self.crosshair, scale);
+		}
+	}
+
+	/// Determines the text of a widget, if it is placed in the given corner.
+	fn text(&self, widget: &Widget, game: &Game, corner: Corner) -> Option<String> {
+		match *widget {
+			Widget::Position(widget_corner) if widget_corner == corner => {
+				Some(format!("X {:.2}  Y {:.2}", game.player.position.x, game.player.position.y))
+			},
+
+			Widget::Facing(widget_corner) if widget_corner == corner => {
+				let degrees = game.player.direction.to_degrees().rem_euclid(360.0);
+				Some(format!("Facing {:.0} deg {}", degrees, Hud::compass_direction(game.player.direction)))
+			},
+
+			Widget::Messages(widget_corner) if widget_corner == corner && !self.messages.is_empty() => {
+				Some(self.messages.iter().map(|(message, _)| message.as_str()).collect::<Vec<&str>>().join("\n"))
+			},
+
+			_ => None
+		}
+	}
+
+	/// Determines the compass direction of an angle. The map's y-axis points south, so angles increase clockwise.
+	fn compass_direction(angle: f64) -> &'static str {
+		let directions = ["E", "SE", "S", "SW", "W", "NW", "N", "NE"];
+		let sector = ((angle.rem_euclid(2.0 * PI) / (PI / 4.0)).round() as usize) % directions.len();
+		directions[sector]
+	}
+}
+
+impl Default for Hud {
+	fn default() -> Hud {
+		Hud::new()
+	}
+}
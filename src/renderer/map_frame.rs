@@ -0,0 +1,77 @@
+use std::ops::Range;
+use utils::geometry::Angle;
+use utils::geometry::Point;
+
+/// Maps between the pixels of a rectangular area on a canvas and points on the map. Used for top-down views of the map.
+pub struct MapFrame {
+	left: isize,
+	top: isize,
+	width: isize,
+	height: isize,
+
+	/// The point of the map shown at the center of the frame
+	origin: Point,
+
+	/// The number of pixels per tile
+	scale: f64,
+
+	/// The angle the map is rotated by
+	rotation: Angle
+}
+
+impl MapFrame {
+	/// Creates a new frame.
+	///
+	/// # Parameters:
+	///		- left, top:		The top left corner of the frame on the canvas
+	///		- width, height:	The size of the frame in pixels
+	///		- origin:			The point of the map shown at the center of the frame
+	///		- scale:			The number of pixels per tile
+	///		- rotation:			The angle the map is rotated by around the origin
+	pub fn new(left: isize, top: isize, width: isize, height: isize, origin: Point, scale: f64, rotation: Angle) -> MapFrame {
+		MapFrame {left, top, width, height, origin, scale, rotation}
+	}
+
+	/// The pixel rows covered by the frame
+	pub fn rows(&self) -> Range<isize> {
+		self.top..(self.top + self.height)
+	}
+
+	/// The pixel columns covered by the frame
+	pub fn columns(&self) -> Range<isize> {
+		self.left..(self.left + self.width)
+	}
+
+	/// The number of pixels per tile
+	pub fn scale(&self) -> f64 {
+		self.scale
+	}
+
+	/// The pixel at the center of the frame
+	pub fn center(&self) -> (isize, isize) {
+		(self.left + self.width / 2, self.top + self.height / 2)
+	}
+
+	/// Converts a canvas pixel to a point on the map.
+	pub fn map_point(&self, x: isize, y: isize) -> Point {
+		let (center_x, center_y) = self.center();
+		let offset_x = (x - center_x) as f64 / self.scale;
+		let offset_y = (y - center_y) as f64 / self.scale;
+		let (sin, cos) = (-self.rotation).sin_cos();
+
+		Point {x: self.origin.x + offset_x * cos - offset_y * sin, y: self.origin.y + offset_x * sin + offset_y * cos}
+	}
+
+	/// Converts a point on the map to a canvas pixel.
+	pub fn canvas_point(&self, point: &Point) -> (isize, isize) {
+		let (center_x, center_y) = self.center();
+		let offset_x = point.x - self.origin.x;
+		let offset_y = point.y - self.origin.y;
+		let (sin, cos) = self.rotation.sin_cos();
+
+		(
+			center_x + ((offset_x * cos - offset_y * sin) * self.scale).round() as isize,
+			center_y + ((offset_x * sin + offset_y * cos) * self.scale).round() as isize
+		)
+	}
+}
@@ -0,0 +1,193 @@
+use utils::canvas::Canvas;
+use utils::color::RGBColor;
+use utils::font;
+
+/// A menu drawn on top of everything else. Shows the items of a single screen, which can be chosen or adjusted (e.g. a setting with a
+/// value). The screens and what happens when an item is chosen are defined by the application.
+pub struct Menu {
+	/// Whether the menu is drawn
+	pub visible: bool,
+
+	/// The title shown above the items
+	pub title: String,
+
+	/// The items that can be chosen
+	pub items: Vec<MenuItem>,
+
+	/// The index of the selected item
+	pub selected: usize,
+
+	/// A hint shown below the items (e.g. which key to press)
+	pub footer: String
+}
+
+/// An item of a menu.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MenuItem {
+	/// The text of the item
+	pub label: String,
+
+	/// The current value of a setting, shown next to the label
+	pub value: Option<String>
+}
+
+/// The position of the menu's parts on a canvas.
+struct Layout {
+	/// The size of a font pixel of the items in canvas pixels
+	scale: usize,
+
+	/// The top left corner of the title
+	title: (isize, isize),
+
+	/// The top left corner of the first item
+	items: (isize, isize),
+
+	/// The width of the widest item
+	items_width: usize,
+
+	/// The top left corner of the footer
+	footer: (isize, isize)
+}
+
+impl Menu {
+	/// The opacity of the background darkening the scene behind the menu
+	const BACKGROUND_ALPHA: u8 = 160;
+
+	/// The space between the title, the items and the footer (in font pixels)
+	const SPACING: usize = 8;
+
+	/// The distance between the tops of two items (in font pixels)
+	const ITEM_HEIGHT: usize = font::LINE_HEIGHT + 4;
+
+	/// Creates a hidden menu without items.
+	pub fn new() -> Menu {
+		Menu {visible: false, title: String::new(), items: Vec::new(), selected: 0, footer: String::new()}
+	}
+
+	/// Shows a screen of the menu with the first item selected.
+	pub fn show(&mut self, title: &str, items: Vec<MenuItem>) {
+		self.visible = true;
+		self.title = title.to_string();
+		self.items = items;
+		self.selected = 0;
+		self.footer.clear();
+	}
+
+	/// Selects the next item. Wraps around to the first item.
+	pub fn select_next(&mut self) {
+		if !self.items.is_empty() {
+			self.selected = (self.selected + 1) % self.items.len();
+		}
+	}
+
+	/// Selects the previous item. Wraps around to the last item.
+	pub fn select_previous(&mut self) {
+		if !self.items.is_empty() {
+			self.selected = (self.selected + self.items.len() - 1) % self.items.len();
+		}
+	}
+
+	/// Determines the item at a position of the canvas the menu is drawn to (e.g. below the mouse cursor).
+	///
+	/// # Parameters:
+	///		- x, y:		The position on the canvas
+	///		- scale:	The scale the menu is drawn with
+	///		- width:	The width of the canvas
+	///		- height:	The height of the canvas
+	pub fn item_at(&self, x: isize, y: isize, scale: usize, width: usize, height: usize) -> Option<usize> {
+		let layout = self.layout(scale, width, height);
+		let item_height = (Menu::ITEM_HEIGHT * layout.scale) as isize;
+
+		if x < layout.items.0 || x >= layout.items.0 + layout.items_width as isize || y < layout.items.1 {
+			return None;
+		}
+
+		let index = ((y - layout.items.1) / item_height) as usize;
+		if index < self.items.len() { Some(index) } else { None }
+	}
+
+	/// Draws the menu centered on top of a canvas, darkening everything behind it.
+	///
+	/// # Parameters:
+	///		- scale:	The size of a font pixel of the items in canvas pixels. The title is twice as large.
+	///		- canvas:	The canvas that should be drawn to.
+	pub fn draw(&self, scale: usize, canvas: &mut Canvas) {
+		if !self.visible {
+			return;
+		}
+
+		let (width, height) = (canvas.width(), canvas.height());
+		canvas.blend_rect(0, 0, width, height, &RGBColor::black(), Menu::BACKGROUND_ALPHA);
+
+		let layout = self.layout(scale, width, height);
+		canvas.draw_text(layout.title.0, layout.title.1, &self.title, &RGBColor::white(), 2 * layout.scale);
+
+		for (index, text) in self.item_texts().iter().enumerate() {
+			let color = if index == self.selected { RGBColor::yellow() } else { RGBColor::light_gray() };
+			canvas.draw_text(layout.items.0, layout.items.1 + (index * Menu::ITEM_HEIGHT * layout.scale) as isize, text, &color, layout.scale);
+		}
+
+		canvas.draw_text(layout.footer.0, layout.footer.1, &self.footer, &RGBColor::light_gray(), layout.scale);
+	}
+
+	/// The texts of the items as drawn. The selected item is marked by an arrow and the values are aligned in a column.
+	fn item_texts(&self) -> Vec<String> {
+		let label_width = self.items.iter().filter(|item| item.value.is_some()).map(|item| item.label.chars().count()).max().unwrap_or(0);
+
+		self.items.iter().enumerate().map(|(index, item)| {
+			let marker = if index == self.selected { ">" } else { " " };
+			match item.value {
+				Some(ref value) => format!("{} {:width$}  < {} >", marker, item.label, value, width = label_width),
+				None => format!("{} {}", marker, item.label)
+			}
+		}).collect()
+	}
+
+	/// Determines the position of the title, the items and the footer. The menu is centered on the canvas and shrunk if it doesn't fit.
+	fn layout(&self, scale: usize, width: usize, height: usize) -> Layout {
+		let texts = self.item_texts();
+		let items_size = |scale: usize| {
+			let width = texts.iter().map(|text| Canvas::text_size(text, scale).0).max().unwrap_or(0);
+			(width, texts.len() * Menu::ITEM_HEIGHT * scale)
+		};
+
+		let mut scale = scale.max(1);
+		while scale > 1 && items_size(scale).0 > width {
+			scale -= 1;
+		}
+
+		let (title_width, title_height) = Canvas::text_size(&self.title, 2 * scale);
+		let (footer_width, footer_height) = Canvas::text_size(&self.footer, scale);
+		let (items_width, items_height) = items_size(scale);
+		let spacing = Menu::SPACING * scale;
+
+		let top = (height as isize - (title_height + spacing + items_height + spacing + footer_height) as isize) / 2;
+		let center = |content_width: usize| (width as isize - content_width as isize) / 2;
+
+		Layout {
+			scale,
+			title: (center(title_width), top),
+			items: (center(items_width), top + (title_height + spacing) as isize),
+			items_width,
+			footer: (center(footer_width), top + (title_height + spacing + items_height + spacing) as isize)
+		}
+	}
+}
+
+impl Default for Menu {
+	fn default() -> Menu {
+		Menu::new()
+	}
+}
+
+impl MenuItem {
+	/// Creates an item that can be chosen.
+	pub fn new(label: &str) -> MenuItem {
+		MenuItem {label: label.to_string(), value: None}
+	}
+
+	/// Creates an item showing the value of a setting.
+	pub fn setting(label: &str, value: &str) -> MenuItem {
+		MenuItem {label: label.to_string(), value: Some(value.to_string())}
+	}
+}
@@ -0,0 +1,169 @@
+use game::Game;
+use game::map::Tile;
+use game::map::TilePosition;
+use renderer::map_frame::MapFrame;
+use renderer::ray::Ray;
+use std::f64::consts::FRAC_PI_2;
+use utils::canvas::Canvas;
+use utils::color::RGBColor;
+use utils::geometry::Angle;
+
+/// A small top-down view of the map around the player that is drawn on top of the 3D scene.
+pub struct Minimap {
+	/// Whether the minimap is drawn
+	pub visible: bool,
+
+	/// The corner of the canvas the minimap is placed in
+	pub corner: Corner,
+
+	/// Whether the map is rotated with the player or always points north
+	pub orientation: Orientation,
+
+	/// The number of tiles visible between the player and the minimap's border (i.e. the zoom level)
+	pub radius: f64,
+
+	/// The size of the minimap relative to the shorter side of the canvas
+	pub relative_size: f64
+}
+
+/// The corners of the canvas the minimap can be placed in.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Corner {
+	TopLeft,
+	TopRight,
+	BottomRight,
+	BottomLeft
+}
+
+/// Describes how the minimap is oriented.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Orientation {
+	/// North is always at the top of the minimap
+	NorthUp,
+
+	/// The player's viewing direction is always at the top of the minimap
+	RotateWithPlayer
+}
+
+impl Corner {
+	/// Returns the next corner in clockwise order.
+	pub fn next(&self) -> Corner {
+		match *self {
+			Corner::TopLeft => Corner::TopRight,
+			Corner::TopRight => Corner::BottomRight,
+			Corner::BottomRight => Corner::BottomLeft,
+			Corner::BottomLeft => Corner::TopLeft
+		}
+	}
+}
+
+impl Minimap {
+	/// The smallest and largest number of tiles that can be visible between the player and the border.
+	const MIN_RADIUS: f64 = 2.0;
+	const MAX_RADIUS: f64 = 64.0;
+
+	/// Creates a hidden north-up minimap in the top right corner.
+	pub fn new() -> Minimap {
+		Minimap {visible: false, corner: Corner::TopRight, orientation: Orientation::NorthUp, radius: 8.0, relative_size: 0.3}
+	}
+
+	/// Zooms the minimap by the given factor (> 1: zoom in, < 1: zoom out).
+	pub fn zoom(&mut self, factor: f64) {
+		self.radius = (self.radius / factor).clamp(Minimap::MIN_RADIUS, Minimap::MAX_RADIUS);
+	}
+
+	/// Draws the minimap on top of a canvas.
+	///
+	/// # Parameters:
+	///		- game:				The game whose map and player should be drawn
+	///		- field_of_view:	The horizontal field of view in degrees used for drawing the view frustum
+	///		- canvas:			The canvas that should be drawn to.
+	pub fn draw(&self, game: &Game, field_of_view: f64, canvas: &mut Canvas) {
+		if !self.visible {
+			return;
+		}
+
+		let size = ((canvas.width().min(canvas.height()) as f64) * self.relative_size) as isize;
+		let margin = size / 16;
+		let (left, top) = match self.corner {
+			Corner::TopLeft => (margin, margin),
+			Corner::TopRight => (canvas.width() as isize - size - margin, margin),
+			Corner::BottomRight => (canvas.width() as isize - size - margin, canvas.height() as isize - size - margin),
+			Corner::BottomLeft => (margin, canvas.height() as isize - size - margin)
+		};
+
+		// Rotate the map so that the viewing direction points upwards (i.e. to -π/2 on the canvas' y-axis, which points down).
+		let rotation = match self.orientation {
+			Orientation::NorthUp => 0.0,
+			Orientation::RotateWithPlayer => -FRAC_PI_2 - game.player.direction
+		};
+		let scale = (size as f64 / 2.0) / self.radius;
+		let frame = MapFrame::new(left, top, size, size, game.player.position.clone(), scale, rotation);
+
+		self.draw_tiles(game, &frame, canvas);
+		self.draw_frustum(game, field_of_view, &frame, canvas);
+		self.draw_player(game, &frame, canvas);
+	}
+
+	/// Draws the map tiles covered by the minimap. Each pixel samples the tile beneath it, so rotated maps need no special treatment.
+	fn draw_tiles(&self, game: &Game, frame: &MapFrame, canvas: &mut Canvas) {
+		let background = RGBColor::dark_gray().adjust_light_intensity(0.5);
+
+		for y in frame.rows() {
+			for x in frame.columns() {
+				if !canvas.is_drawable(x, y) {
+					continue;
+				}
+
+				let color = match game.map.tile(&TilePosition::containing(&frame.map_point(x, y))) {
+					Tile::Empty => background.clone(),
+					Tile::Wall(color) => color
+				};
+
+				canvas.draw_pixel(x as usize, y as usize, &color);
+			}
+		}
+	}
+
+	/// Draws the borders of the player's field of view up to the first wall they hit.
+	fn draw_frustum(&self, game: &Game, field_of_view: f64, frame: &MapFrame, canvas: &mut Canvas) {
+		let half_field_of_view = field_of_view.to_radians() / 2.0;
+
+		for relative_angle in [-half_field_of_view, half_field_of_view].iter() {
+			let length = self.visible_ray_length(game, game.player.direction + relative_angle);
+			let end = game.player.position.add(length, game.player.direction + relative_angle);
+
+			canvas.draw_line(frame.center(), frame.canvas_point(&end), &RGBColor::light_gray());
+		}
+	}
+
+	/// Draws the player's position and viewing direction.
+	fn draw_player(&self, game: &Game, frame: &MapFrame, canvas: &mut Canvas) {
+		let (x, y) = frame.center();
+		let nose = game.player.position.add(self.radius / 4.0, game.player.direction);
+
+		canvas.draw_line((x, y), frame.canvas_point(&nose), &RGBColor::white());
+		canvas.fill_rect(x - 1, y - 1, 3, 3, &RGBColor::white());
+	}
+
+	/// Determines the length of a ray from the player until it hits a wall, limited to the visible radius of the minimap.
+	fn visible_ray_length(&self, game: &Game, angle: Angle) -> f64 {
+		let mut ray = Ray::new(game.player.position.clone(), angle);
+
+		while ray.length < self.radius {
+			ray.grow();
+
+			if let Tile::Wall(_) = game.map.tile(&ray.tile) {
+				break;
+			}
+		}
+
+		ray.length.min(self.radius)
+	}
+}
+
+impl Default for Minimap {
+	fn default() -> Minimap {
+		Minimap::new()
+	}
+}
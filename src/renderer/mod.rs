@@ -1,67 +1,257 @@
-mod ray;
+pub mod automap;
+pub mod console;
+pub mod debug_view;
+pub mod fog;
+pub mod hud;
+mod map_frame;
+pub mod menu;
+pub mod minimap;
+pub mod profiler;
+pub mod ray;
 
 use game::Game;
-use game::map::Tile;
-use game::map::TilePosition;
 use game::map::Map;
+use renderer::automap::Automap;
+use renderer::console::Console;
+use renderer::debug_view::DebugView;
+use renderer::fog::Fog;
+use renderer::hud::Hud;
+use renderer::menu::Menu;
+use renderer::minimap::Minimap;
+use renderer::profiler::Profiler;
+use renderer::profiler::RenderTiming;
 use renderer::ray::Ray;
+use std::thread;
+use std::time::Instant;
+use utils::canvas::Canvas;
+use utils::color::LightColor;
 use utils::color::RGBColor;
 use utils::geometry::Angle;
-use window::canvas::Canvas;
+use utils::pixel_format::PixelFormat;
 
 /// Draws a 3D scene for a given map and a player within the map.
 pub struct Renderer {
 	/// The state of the virtual world to be rendered
 	pub game: Game,
 
-	/// The size of the physical computer display in relation to a grid field
-	pub relative_screen_size: f64,
-
-	/// The focal length used for determining the window angle
-	pub focal_length: f64,
+	/// The horizontal field of view in degrees. The vertical field of view is derived from the canvas' aspect ratio.
+	pub field_of_view: f64,
 
 	/// The radius around the player where objects should appear illuminated
 	pub illumination_radius: f64,
 
 	/// The minimum environment light of the scene
-	pub minimum_light: f64
+	pub minimum_light: f64,
+
+	/// The fog the scene fades to with increasing distance
+	pub fog: Fog,
+
+	/// The number of threads used for rendering a frame. Each thread renders its own strip of columns.
+	pub thread_count: usize,
+
+	/// The minimap drawn on top of the 3D scene
+	pub minimap: Minimap,
+
+	/// The map of the explored areas, shown instead of the 3D scene
+	pub automap: Automap,
+
+	/// The split screen view showing the casted rays, shown instead of the 3D scene
+	pub debug_view: DebugView,
+
+	/// The head-up display drawn on top of the scene and the maps
+	pub hud: Hud,
+
+	/// The menu drawn on top of the HUD
+	pub menu: Menu,
+
+	/// The developer console drawn on top of the menu
+	pub console: Console,
+
+	/// Collects the frame timings and draws the performance overlay on top of the HUD
+	pub profiler: Profiler,
+
+	/// The number of canvas pixels per logical point of the display (e.g. 2 on high-DPI displays, less than 1 if frames are upscaled).
+	/// The HUD and other overlays are scaled by it, so they keep their physical size.
+	pub scale_factor: f64
 }
 
 impl Renderer {
-	/// Initializes the renderer with a map, a player and a focal length that should be used for rendering.
+	/// Initializes the renderer with a map, a player and a field of view that should be used for rendering.
 	///
 	/// # Parameters:
 	/// 	- game:						The virtual world state (i.e. the game's map and player position)
-	///		- relative_screen_size:		The size of the physical computer display in relation to a grid field
-	///  	- focal_length:				A focal length that should be used for rendering.
+	///		- field_of_view:			The horizontal field of view in degrees.
 	///	 	- illumination_radius:		The radius around the player where objects should appear illuminated.
-	///	 	- minimum_öight:			The minimum environment light of the scene.
+	///	 	- minimum_light:			The minimum environment light of the scene.
+	///		- profiler:					Collects the frame timings.
 	///
-	pub fn new(game: Game, relative_screen_size: f64, focal_length: f64, illumination_radius: f64, minimum_light: f64) -> Renderer {
-		Renderer {game, relative_screen_size, focal_length, illumination_radius, minimum_light}
+	pub fn new(game: Game, field_of_view: f64, illumination_radius: f64, minimum_light: f64, profiler: Profiler) -> Renderer {
+		let thread_count = thread::available_parallelism().map_or(1, |count| count.get());
+		Renderer {
+			game, field_of_view, illumination_radius, minimum_light, fog: Fog::new(), thread_count,
+			minimap: Minimap::new(), automap: Automap::new(), debug_view: DebugView::new(), hud: Hud::new(), menu: Menu::new(),
+			console: Console::new(), profiler, scale_factor: 1.0
+		}
 	}
 
-	/// Renders one frame into a canvas.
+	/// Renders one frame into a canvas. Overlays like the minimap are drawn on top of the 3D scene. If the automap or the debug view is
+	/// visible, it is drawn instead of the 3D scene. The HUD, the menu, the console and the performance overlay are drawn on top of
+	/// everything else.
+	/// Returns the time spent on ray casting and drawing.
 	///
 	/// # Parameters:
 	///		- canvas		The canvas that should be drawn to.
-	pub fn render(&self, canvas: &mut Canvas) {
-		for column in 0..canvas.width() {
-			self.render_column(column, canvas);
+	pub fn render(&self, canvas: &mut Canvas) -> RenderTiming {
+		let start = Instant::now();
+		let mut timing = RenderTiming::default();
+
+		if self.automap.visible {
+			self.automap.draw(&self.game, canvas);
+		}
+		else if self.debug_view.visible {
+			self.debug_view.draw(self, canvas);
 		}
+		else {
+			timing = self.render_scene(canvas);
+			self.minimap.draw(&self.game, self.field_of_view, canvas);
+		}
+
+		self.hud.draw(&self.game, self.overlay_scale(), canvas);
+		self.menu.draw(self.overlay_scale(), canvas);
+		self.console.draw(self.overlay_scale(), canvas);
+		self.profiler.draw(self.overlay_scale(), canvas);
+
+		// Everything besides casting the rays of the 3D scene counts as drawing
+		timing.drawing = start.elapsed() - timing.ray_casting;
+		timing
+	}
+
+	/// The size of a font pixel of the overlays in canvas pixels. A font pixel covers two logical points of the display.
+	pub fn overlay_scale(&self) -> usize {
+		(2.0 * self.scale_factor).round().max(1.0) as usize
+	}
+
+	/// Renders the 3D scene into a canvas. The columns of the frame are independent of each other, so the frame is split into strips of
+	/// columns that are rendered in parallel. Returns the time spent on ray casting and drawing by the slowest strips.
+	///
+	/// # Parameters:
+	///		- canvas		The canvas that should be drawn to.
+	#[cfg(not(target_os = "emscripten"))]
+	fn render_scene(&self, canvas: &mut Canvas) -> RenderTiming {
+		let projection = Projection::new(self.field_of_view, canvas.width());
+		let mut strips = canvas.split_columns(self.thread_count);
+
+		let timings: Vec<RenderTiming> = thread::scope(|scope| {
+			let threads: Vec<_> = strips.iter_mut().map(|strip| {
+				let projection = &projection;
+				scope.spawn(move || self.render_strip(projection, strip))
+			}).collect();
+
+			threads.into_iter().map(|thread| thread.join().unwrap()).collect()
+		});
+
+		RenderTiming::parallel(&timings)
 	}
 
-	/// Renders one pixel column of a frame into a canvas.
+	/// Renders the 3D scene into a canvas. Web targets do not support threads, so all columns are rendered sequentially.
+	/// Returns the time spent on ray casting and drawing.
 	///
 	/// # Parameters:
-	///		- column:		The pixel column of the canvas that should be rendered
+	///		- canvas		The canvas that should be drawn to.
+	#[cfg(target_os = "emscripten")]
+	fn render_scene(&self, canvas: &mut Canvas) -> RenderTiming {
+		let projection = Projection::new(self.field_of_view, canvas.width());
+		self.render_strip(&projection, canvas)
+	}
+
+	/// Renders all columns of a canvas. All rays are casted before the columns are drawn, so both stages can be timed separately.
+	///
+	/// # Parameters:
+	///		- projection:	The projection of the current frame
 	///		- canvas:		The canvas that should be drawn to.
-	fn render_column(&self, column: usize, canvas: &mut Canvas) {
-		// Cast the ray to find a nearby wall
-		let scanning_result = self.cast_ray(column, canvas.width());
+	fn render_strip(&self, projection: &Projection, canvas: &mut Canvas) -> RenderTiming {
+		// Cast the rays to find nearby walls
+		let start = Instant::now();
+		let hits: Vec<Hit> = canvas.columns().map(|column| self.cast_ray(projection.ray_angle(column))).collect();
+		let ray_casting = start.elapsed();
+
+		// Draw the scanning results to the canvas
+		let start = Instant::now();
+		let background = self.background(projection, canvas.height(), canvas.format());
+		for (column, hit) in canvas.columns().zip(hits) {
+			self.draw_hit(hit, column, projection, &background, canvas);
+		}
 
-		// Draw scanning result to the canvas
-		self.draw_hit(scanning_result, column, canvas);
+		RenderTiming {ray_casting, drawing: start.elapsed()}
+	}
+}
+
+/// Describes how the virtual camera plane is mapped onto the pixels of a canvas.
+struct Projection {
+	/// Half the width of the camera plane at a distance of one grid field (i.e. `tan(fov/2)`)
+	half_plane_width: f64,
+
+	/// The distance of the camera plane in pixels. Used for both axes, so pixels stay square regardless of the aspect ratio.
+	focal_length: f64,
+
+	/// The width of the canvas in pixels
+	width: usize
+}
+
+impl Projection {
+	/// Creates the projection for a canvas of the given width. The vertical field of view follows from the canvas' aspect ratio
+	/// (`2 * atan(tan(fov/2) * height / width)`), since the same focal length is used for both axes.
+	///
+	/// # Parameters:
+	///		- field_of_view:	The horizontal field of view in degrees.
+	///		- width:			The width of the canvas in pixels.
+	fn new(field_of_view: f64, width: usize) -> Projection {
+		let half_plane_width = (field_of_view.clamp(1.0, 179.0).to_radians() / 2.0).tan();
+		let focal_length = (width as f64 / 2.0) / half_plane_width;
+
+		Projection {half_plane_width, focal_length, width}
+	}
+
+	/// Determines the angle of a scanning ray (relative to the player's viewing direction) for drawing the given column.
+	/// The ray passes through the center of the column on the camera plane.
+	///
+	///	# Parameters:
+	///		- column:	The current view column to be drawn (which must be less than the view's width).
+	fn ray_angle(&self, column: usize) -> Angle {
+		self.camera_angle(self.camera_position(column))
+	}
+
+	/// Determines the angle of a scanning ray (relative to the player's viewing direction) passing through a position on the camera plane.
+	///
+	///	# Parameters:
+	///		- camera_position:	The horizontal position on the camera plane (-1: left border, 1: right border).
+	fn camera_angle(&self, camera_position: f64) -> Angle {
+		(camera_position * self.half_plane_width).atan()
+	}
+
+	/// Determines the horizontal position of the center of a column on the camera plane (-1: left border, 1: right border).
+	fn camera_position(&self, column: usize) -> f64 {
+		2.0 * (column as f64 + 0.5) / (self.width as f64) - 1.0
+	}
+
+	/// Determines the column containing a horizontal position on the camera plane (-1: left border, 1: right border).
+	fn column(&self, camera_position: f64) -> usize {
+		let column = ((camera_position + 1.0) / 2.0 * self.width as f64) as usize;
+		column.min(self.width.max(1) - 1)
+	}
+
+	/// Determines the height in pixels of a wall at the given perpendicular distance.
+	fn wall_height(&self, distance: f64) -> f64 {
+		self.focal_length / distance
+	}
+
+	/// Determines the perpendicular distance of the floor or the ceiling seen at a row of the canvas.
+	///
+	///	# Parameters:
+	///		- height:		The height of the floor or the ceiling relative to the eyes, in wall heights
+	///		- row_offset:	The distance of the row from the horizon in pixels
+	fn plane_distance(&self, height: f64, row_offset: f64) -> f64 {
+		height * self.focal_length / row_offset
 	}
 }
 
@@ -76,109 +266,127 @@ enum Hit {
 
 // Methods related to ray casting
 impl Renderer {
-	/// Casts a ray from the player's position for a given column on the view and returns what the ray scanned at its end.
+	/// Casts a ray from the player's position and returns what the ray scanned at its end.
 	///
 	/// # Parameters:
-	///		- column:			The column that should be drawn
-	///		- width:			The largest column number that could be drawn
-	fn cast_ray(&self, column: usize, max_column: usize) -> Hit {
+	///		- relative_angle:	The angle of the ray relative to the player's viewing direction
+	fn cast_ray(&self, relative_angle: Angle) -> Hit {
 		// Determine the absolute angle of the ray
-		let relative_angle = self.ray_angle(column, max_column);
 		let absolute_angle = relative_angle + self.game.player.direction;
 
 		// Create the ray
 		let mut ray = Ray::new(self.game.player.position.clone(), absolute_angle);
+		self.game.exploration.mark_tile(&ray.tile);
 
-		// Grow the ray stepy by step. Grow it until we either hit a wall or reached the maximal possible distance inside our map
-		while ray.length <= self.game.map.max_distance() as f64 {
-			ray = ray.grow();
-
-			let tile = self.game.map.tile(&TilePosition::new(&ray.end, &ray.angle));
-			match tile {
-				Tile::Empty => {
-					// We've found nothing. Just continue scanning.
-				},
-
-				Tile::Wall(color) => {
-					// Fix the calculated distance to correct the fisheye effect
-					let projected_distance = ray.length * relative_angle.cos();	
-						
-					// Apply some lighting to the wall's color
-					let wall_light_intensity = Map::light_intensity_for_wall(ray.end, ray.angle);
-					let distance_light_intensity = (1.0 - ray.length/self.illumination_radius).max(self.minimum_light).min(1.0);
-					let illuminated_color = color.adjust_light_intensity(distance_light_intensity * wall_light_intensity);
-
-					// Pass the result
-					return Hit::Wall {color: illuminated_color, distance: projected_distance}
-				}
-			}
-		}	
+		// Grow the ray step by step until we either hit a wall or reached the maximal possible distance inside our map. We remember
+		// every empty tile the ray passed, since the player has seen its floor.
+		let color = match ray.cast(&self.game.map, |ray| self.game.exploration.mark_tile(&ray.tile)) {
+			Some(color) => color,
 
-		// The ray casting reached the outer bounds of our map. We never hit a wall...
-		return Hit::None;
+			// The ray casting reached the outer bounds of our map. We never hit a wall...
+			None => { return Hit::None; }
+		};
+
+		// Remember that the player has seen this wall
+		if let Some(face) = ray.face {
+			self.game.exploration.mark_face(&ray.tile, face);
+		}
+
+		// Fix the calculated distance to correct the fisheye effect
+		let projected_distance = ray.length * relative_angle.cos();
+
+		// Apply some lighting to the wall's color: the player-centered light plus the light of the map's lights and the flashlight
+		let wall_light_intensity = ray.face.map_or(1.0, Map::light_intensity_for_wall);
+		let distance_light_intensity = (1.0 - ray.length/self.illumination_radius).max(self.minimum_light).min(1.0);
+		let light = LightColor::white(distance_light_intensity * wall_light_intensity)
+			.add(&ray.face.map_or(LightColor::dark(), |face| self.game.light_at(&ray.tile, face, &ray.end)));
+
+		Hit::Wall {color: color.tint(&light), distance: projected_distance}
 	}
 
-	/// Determines the angle of a scanning ray for drawing the given column on a view with the given width.
-	/// The ray should be casted from the given player's using its position, viewing direction and the current focal length.
+	/// Casts the ray of a single column exactly like `render` does, i.e. including marking the explored tiles and lighting the hit
+	/// wall. Only meant for benchmarks measuring a single ray. Returns the color and the distance of the hit wall, if any.
 	///
-	///	# Parameters:
-	///		- column:	The current view column to be drawn (which must be less than the view's width).
-	///		- width:	The width of the view.
-	fn ray_angle(&self, column: usize, max_column: usize) -> Angle {
-		let relative_position = ((column as f64) / (max_column as f64)) - 0.5;
-		let virtual_screen_position = relative_position * self.relative_screen_size;
-		return (virtual_screen_position / self.focal_length).atan();
+	/// # Parameters:
+	///		- relative_angle:	The angle of the ray relative to the player's viewing direction
+	#[doc(hidden)]
+	pub fn cast_ray_for_benchmark(&self, relative_angle: Angle) -> Option<(RGBColor, f64)> {
+		match self.cast_ray(relative_angle) {
+			Hit::Wall {color, distance} => Some((color, distance)),
+			Hit::None => None
+		}
 	}
 }
 
 // Methods related to drawing
 impl Renderer {
 	/// Draws the given view column for the result of a particular ray casting operation to a given canvas
-	fn draw_hit(&self, hit: Hit, column: usize, canvas: &mut Canvas) {
+	///
+	///	# Parameters:
+	///		- hit:			The result of the ray casted for the column
+	///		- column:		The current view column to be drawn.
+	///		- projection:	The projection of the current frame
+	///		- background:	The encoded colors of the ceiling and the floor for each row (see `background`)
+	///		- canvas:		The canvas that should be used for drawing.
+	fn draw_hit(&self, hit: Hit, column: usize, projection: &Projection, background: &[[u8; 4]], canvas: &mut Canvas) {
 		match hit {
 			// We did not found a wall, just draw an empty space
-			Hit::None => self.draw_wall(0.0, RGBColor::black(), canvas, column),
+			Hit::None => self.draw_wall(0.0, RGBColor::black(), background, canvas, column),
 			
 			Hit::Wall {color, distance} => {
 				// Determine the visual height of the wall on the screen (normalized to the screen's height)
-				let normalized_wall_height = 1.0 / distance;
+				let normalized_wall_height = projection.wall_height(distance) / canvas.height() as f64;
 
 				// Finally: Draw the wall for the current view position…
-				self.draw_wall(normalized_wall_height, color, canvas, column)
+				self.draw_wall(normalized_wall_height, self.fog.apply(&color, distance), background, canvas, column)
 			}
 		}
 	}
 
-	/// Draws a column of a wall for the given view position.
+	/// Determines the colors of the black ceiling and the grey floor gradient for each row of a canvas, covered by fog. All points
+	/// of a row have the same distance from the camera plane, so the colors are the same for all columns and are encoded only once.
 	///
 	///	# Parameters:
-	///  	- wall_height:	The visible height of a wall segment to be drawn (0: no wall, >=1: full view height).
+	///		- projection:	The projection of the current frame
+	///		- height:		The height of the canvas
+	///		- format:		The pixel format of the canvas
+	fn background(&self, projection: &Projection, height: usize, format: PixelFormat) -> Vec<[u8; 4]> {
+		let horizon = height as f64 / 2.0;
+		let eye_height = self.game.eye_height();
+
+		(0..height).map(|y| {
+			let row_offset = y as f64 + 0.5 - horizon;
+			if row_offset < 0.0 {
+				format.encode(&self.fog.apply(&RGBColor::black(), projection.plane_distance(1.0 - eye_height, -row_offset)))
+			}
+			else {
+				let gradient_color = RGBColor::dark_gray().adjust_light_intensity(y as f64 / height as f64);
+				format.encode(&self.fog.apply(&gradient_color, projection.plane_distance(eye_height, row_offset)))
+			}
+		}).collect()
+	}
+
+	/// Draws a column of a wall for the given view position. The horizon is at the center of the canvas, the wall is placed around it
+	/// according to the player's eye height.
+	///
+	///	# Parameters:
+	///  	- wall_height:	The visible height of a wall segment to be drawn (0: no wall, 1: full view height).
 	///  	- color:		The color of the wall to be drawn.
+	///  	- background:	The encoded colors of the ceiling and the floor for each row
 	///  	- canvas:		The canvas that should be used for drawing.
 	///  	- column:		The current view column to be drawn.
-	fn draw_wall(&self, wall_height: f64, color: RGBColor, canvas: &mut Canvas, column: usize) {
+	fn draw_wall(&self, wall_height: f64, color: RGBColor, background: &[[u8; 4]], canvas: &mut Canvas, column: usize) {
 		let window_height = canvas.height();
-		let limited_wall_height = wall_height.min(1.0);
-		let view_wall_height = ((window_height as f64) * limited_wall_height) as usize;
+		let view_wall_height = window_height as f64 * wall_height;
+		let horizon = window_height as f64 / 2.0;
+		let eye_height = self.game.eye_height();
 
-		let wall_top = (window_height - view_wall_height) / 2;
-		let wall_bottom = wall_top + view_wall_height;
+		let wall_top = (horizon - view_wall_height * (1.0 - eye_height)).clamp(0.0, horizon) as usize;
+		let wall_bottom = (horizon + view_wall_height * eye_height).clamp(horizon, window_height as f64) as usize;
 
-		// Draw the black ceiling
-		for y in 0..wall_top {
-			canvas.draw_pixel(column, y, &RGBColor::black());
-		}
-
-		// Draw the wall (if anything is visible)
-		for y in wall_top..wall_bottom {
-			canvas.draw_pixel(column as usize, y as usize, &color);
-		}
-
-		// Draw the floor as grey gradient
-		for y in wall_bottom..window_height {
-			let gradient_position = y as f64 / window_height as f64;
-			let gradient_color = RGBColor::dark_gray().adjust_light_intensity(gradient_position);
-			canvas.draw_pixel(column as usize, y as usize, &gradient_color);
-		}
+		// Draw the ceiling, the wall (if anything is visible) and the floor
+		canvas.copy_column(column, 0..wall_top, &background[..wall_top]);
+		canvas.fill_column(column, wall_top..wall_bottom, &color);
+		canvas.copy_column(column, wall_bottom..window_height, &background[wall_bottom..]);
 	}
 }
@@ -0,0 +1,200 @@
+use std::collections::VecDeque;
+use std::io;
+use std::io::Write;
+use std::time::Duration;
+use std::time::Instant;
+use utils::canvas::Canvas;
+use utils::color::RGBColor;
+
+/// Collects the timings of rendered frames and shows them on an overlay.
+pub struct Profiler {
+	/// Whether the performance overlay is drawn
+	pub visible: bool,
+
+	/// The most recent frames shown on the overlay
+	history: VecDeque<FrameTiming>,
+
+	/// All frames since the start, if they should be exported
+	log: Option<Vec<FrameTiming>>,
+
+	/// The time the previous frame was started
+	previous_frame_start: Option<Instant>
+}
+
+/// The time spent on the different stages of a single frame.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FrameTiming {
+	/// The time between the start of the previous frame and the start of this frame
+	pub frame: Duration,
+
+	/// Handling events and updating the game state
+	pub simulation: Duration,
+
+	/// Casting the rays of the 3D scene
+	pub ray_casting: Duration,
+
+	/// Drawing the columns of the 3D scene and all overlays
+	pub drawing: Duration,
+
+	/// Transferring the frame to the screen
+	pub present: Duration
+}
+
+/// The time spent on the stages of rendering a frame.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RenderTiming {
+	/// Casting the rays of the 3D scene
+	pub ray_casting: Duration,
+
+	/// Drawing the columns of the 3D scene and all overlays
+	pub drawing: Duration
+}
+
+impl Profiler {
+	/// The number of frames shown in the frame time graph
+	const HISTORY_LENGTH: usize = 120;
+
+	/// The number of frames used for the averages shown on the overlay
+	const AVERAGE_LENGTH: usize = 30;
+
+	/// The frame time drawn as full graph height (in milliseconds)
+	const GRAPH_RANGE: f64 = 50.0;
+
+	/// Creates a new profiler with a hidden overlay.
+	///
+	/// # Parameters:
+	///		- log_frames:	Whether the timings of all frames should be kept for exporting them.
+	pub fn new(log_frames: bool) -> Profiler {
+		let log = if log_frames { Some(Vec::new()) } else { None };
+		Profiler {visible: false, history: VecDeque::with_capacity(Profiler::HISTORY_LENGTH), log, previous_frame_start: None}
+	}
+
+	/// Records the timing of a rendered frame.
+	///
+	/// # Parameters:
+	///		- frame_start:	The time the frame was started
+	///		- timing:		The time spent on the stages of the frame. The frame time is determined by the profiler.
+	pub fn record(&mut self, frame_start: Instant, timing: FrameTiming) {
+		let frame = self.previous_frame_start.map_or(Duration::from_secs(0), |previous| frame_start.duration_since(previous));
+		let timing = FrameTiming {frame, ..timing};
+		self.previous_frame_start = Some(frame_start);
+
+		if self.history.len() == Profiler::HISTORY_LENGTH {
+			self.history.pop_front();
+		}
+		self.history.push_back(timing);
+
+		if let Some(ref mut log) = self.log {
+			log.push(timing);
+		}
+	}
+
+	/// Writes the timings of all frames as CSV (in milliseconds). Nothing is written, if the profiler does not log frames.
+	pub fn write_csv<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+		let log = match self.log {
+			Some(ref log) => log,
+			None => return Ok(())
+		};
+
+		writeln!(writer, "frame,frame_time,simulation,ray_casting,drawing,present")?;
+		for (index, timing) in log.iter().enumerate() {
+			writeln!(
+				writer, "{},{:.3},{:.3},{:.3},{:.3},{:.3}",
+				index, milliseconds(timing.frame), milliseconds(timing.simulation), milliseconds(timing.ray_casting),
+				milliseconds(timing.drawing), milliseconds(timing.present)
+			)?;
+		}
+
+		Ok(())
+	}
+
+	/// Draws the frame rate, the average timings and a frame time graph into the top right corner of a canvas.
+	///
+	/// # Parameters:
+	///		- scale:	The size of a font pixel in canvas pixels
+	///		- canvas:	The canvas that should be drawn to.
+	pub fn draw(&self, scale: usize, canvas: &mut Canvas) {
+		if !self.visible || self.history.is_empty() {
+			return;
+		}
+
+		let margin = 4 * scale as isize;
+
+		// Averages of the most recent frames
+		let recent: Vec<&FrameTiming> = self.history.iter().rev().take(Profiler::AVERAGE_LENGTH).collect();
+		let average = |stage: &dyn Fn(&FrameTiming) -> Duration| recent.iter().map(|timing| milliseconds(stage(timing))).sum::<f64>() / recent.len() as f64;
+		let frame_time = average(&|timing| timing.frame);
+		let frame_rate = if frame_time > 0.0 { 1000.0 / frame_time } else { 0.0 };
+
+		let text = format!(
+			"FPS      {:6.1}\nFrame    {:6.2} ms\nSimulate {:6.2} ms\nCast     {:6.2} ms\nDraw     {:6.2} ms\nPresent  {:6.2} ms",
+			frame_rate, frame_time, average(&|timing| timing.simulation), average(&|timing| timing.ray_casting),
+			average(&|timing| timing.drawing), average(&|timing| timing.present)
+		);
+
+		let (text_width, text_height) = Canvas::text_size(&text, scale);
+		let graph_width = Profiler::HISTORY_LENGTH * scale;
+		let graph_height = 40 * scale;
+		let width = text_width.max(graph_width) as isize;
+		let left = canvas.width() as isize - width - margin;
+		let graph_top = margin + text_height as isize + margin;
+
+		let padding = 2 * scale as isize;
+		let box_height = graph_top + graph_height as isize - margin;
+		canvas.blend_rect(left - padding, margin - padding, (width + 2 * padding) as usize, (box_height + 2 * padding) as usize, &RGBColor::black(), 160);
+		canvas.draw_text(left, margin, &text, &RGBColor::white(), scale);
+
+		self.draw_graph(canvas, left + width - graph_width as isize, graph_top, scale, graph_height);
+	}
+
+	/// Draws a bar for each recent frame. The bars are stacked by the stages of the frame, the remaining frame time is drawn gray.
+	fn draw_graph(&self, canvas: &mut Canvas, left: isize, top: isize, scale: usize, height: usize) {
+		let pixels_per_millisecond = height as f64 / Profiler::GRAPH_RANGE;
+		let bottom = top + height as isize;
+
+		for (index, timing) in self.history.iter().enumerate() {
+			let x = left + (index * scale) as isize;
+			let stages = [
+				(milliseconds(timing.simulation), RGBColor::white()),
+				(milliseconds(timing.ray_casting), RGBColor::yellow()),
+				(milliseconds(timing.drawing), RGBColor::green()),
+				(milliseconds(timing.present), RGBColor::blue())
+			];
+
+			// The remaining frame time was spent outside the measured stages (e.g. waiting for the next frame)
+			let measured: f64 = stages.iter().map(|&(duration, _)| duration).sum();
+			let remainder = (milliseconds(timing.frame) - measured).max(0.0);
+
+			// Stack the stages on top of each other
+			let mut stacked = 0.0;
+			for &(duration, ref color) in stages.iter().chain([(remainder, RGBColor::dark_gray())].iter()) {
+				let bar_top = bottom - ((stacked + duration).min(Profiler::GRAPH_RANGE) * pixels_per_millisecond) as isize;
+				let bar_bottom = bottom - (stacked.min(Profiler::GRAPH_RANGE) * pixels_per_millisecond) as isize;
+				if bar_bottom > bar_top {
+					canvas.fill_rect(x, bar_top, scale, (bar_bottom - bar_top) as usize, color);
+				}
+
+				stacked += duration;
+			}
+		}
+
+		// Mark the frame time of 60 frames per second
+		let target_y = bottom - (1000.0 / 60.0 * pixels_per_millisecond) as isize;
+		canvas.draw_line((left, target_y), (left + (Profiler::HISTORY_LENGTH * scale) as isize - 1, target_y), &RGBColor::red());
+	}
+}
+
+impl RenderTiming {
+	/// Combines the timings of stages that ran in parallel. A stage takes as long as its slowest part.
+	pub fn parallel(timings: &[RenderTiming]) -> RenderTiming {
+		RenderTiming {
+			ray_casting: timings.iter().map(|timing| timing.ray_casting).max().unwrap_or_default(),
+			drawing: timings.iter().map(|timing| timing.drawing).max().unwrap_or_default()
+		}
+	}
+}
+
+/// Converts a duration to milliseconds.
+fn milliseconds(duration: Duration) -> f64 {
+	duration.as_secs_f64() * 1000.0
+}
@@ -1,82 +1,226 @@
+use game::map::Face;
+use game::map::Map;
+use game::map::Tile;
+use game::map::TilePosition;
+use utils::color::RGBColor;
 use utils::geometry::Angle;
-use utils::geometry::Axis;
-use utils::geometry::Direction;
 use utils::geometry::Point;
 
-/// Describes a Ray that move through a map
+/// Describes a Ray that move through a map. The ray is traversed from grid line to grid line using a digital differential analyzer (DDA):
+/// the distances between two grid lines along the ray are computed once and then simply accumulated on each step.
+/// See: https://lodev.org/cgtutor/raycasting.html
 pub struct Ray {
 	/// The starting point of the ray
 	pub start:	Point,
 
 	/// The ending point of the ray
 	pub end:	Point,
-	
-	/// The angle of the ray
-	pub angle:	Angle,
 
 	/// The length of the ray
-	pub length:	f64
+	pub length:	f64,
+
+	/// The tile the ray's end point has entered last
+	pub tile:	TilePosition,
+
+	/// The face of `tile` the ray has entered through (`None` as long as the ray did not leave its starting tile)
+	pub face:	Option<Face>,
+
+	/// The unit vector pointing in the ray's direction
+	direction:	Point,
+
+	/// The tile offset applied when crossing a grid line that is parallel to the Y axis (resp. X axis)
+	step_x:		isize,
+	step_y:		isize,
+
+	/// The length along the ray between two grid lines that are parallel to the Y axis (resp. X axis)
+	delta_x:	f64,
+	delta_y:	f64,
+
+	/// The ray length at which the next grid line that is parallel to the Y axis (resp. X axis) is crossed
+	next_x:		f64,
+	next_y:		f64
 }
 
 // Methods related to ray casting
 impl Ray {
+	/// Direction components below this magnitude are rounding errors of axis-aligned angles (e.g. `cos(π/2)` is not exactly zero).
+	const EPSILON: f64 = 1e-9;
+
 	/// Initializes a ray with a starting point and an angle. The ray's end will be set to its start.
-	pub fn new(start: Point, angle: f64) -> Ray {
-		Ray::new_with_end(start.clone(), start, angle)
-	}
+	pub fn new(start: Point, angle: Angle) -> Ray {
+		let direction = Point {x: angle.cos(), y: angle.sin()};
+		let tile = TilePosition::containing(&start);
 
-	/// Initializes a ray with a start position, an end position and an angle.
-	fn new_with_end(start: Point, end: Point, angle: f64) -> Ray {
-		let delta_x = end.x - start.x;
-		let delta_y = end.y - start.y;
-		let length = (delta_x * delta_x + delta_y * delta_y).sqrt();
+		let (step_x, delta_x, next_x) = Ray::traversal_for_component(start.x, direction.x);
+		let (step_y, delta_y, next_y) = Ray::traversal_for_component(start.y, direction.y);
 
-		Ray {start, end, angle, length}
+		Ray {
+			end: start.clone(), start, length: 0.0, tile, face: None,
+			direction, step_x, step_y, delta_x, delta_y, next_x, next_y
+		}
 	}
 
-	/// Creates a new ray where the end point moved one step to the next grid line that is parallel either to the X axis or Y axis.
-	pub fn grow(&self) -> Ray {
-		// Simulate the ray is hitting the next grid line that is parallel either to the X or to the Y axis
-		let ray_on_next_x_line = self.grow_to_next_x_line();
-		let ray_on_next_y_line = self.grow_to_next_y_line();
+	/// Grows the ray step by step until it either hits a wall or reaches the longest possible distance inside a map. Returns the color
+	/// of the wall that was hit.
+	///
+	/// # Parameters:
+	///		- map:			The map the ray is casted through
+	///		- visit_empty:	Called for every empty tile the ray enters, after the ray has grown into it
+	pub fn cast<F: FnMut(&Ray)>(&mut self, map: &Map, mut visit_empty: F) -> Option<RGBColor> {
+		while self.length <= map.max_distance() as f64 {
+			self.grow();
 
-		// Choose the candidate with the shorter distance to the current point.
-		if ray_on_next_x_line.length < ray_on_next_y_line.length {
-			return ray_on_next_x_line
+			match map.tile(&self.tile) {
+				Tile::Empty => visit_empty(self),
+				Tile::Wall(color) => { return Some(color); }
+			}
+		}
+
+		None
+	}
+
+	/// Moves the end point of the ray one step to the next grid line that is parallel either to the X axis or Y axis.
+	pub fn grow(&mut self) {
+		// Choose the grid line that is closer to the current point.
+		if self.next_x <= self.next_y {
+			self.length = self.next_x;
+			self.next_x += self.delta_x;
+			self.tile.x += self.step_x;
+			self.face = Some(if self.step_x > 0 { Face::West } else { Face::East });
 		}
 		else {
-			return ray_on_next_y_line
+			self.length = self.next_y;
+			self.next_y += self.delta_y;
+			self.tile.y += self.step_y;
+			self.face = Some(if self.step_y > 0 { Face::North } else { Face::South });
 		}
-	}
 
-	/// Creates a new ray where the end point progressed to the next grid line that is parallel to the X axis.
-	fn grow_to_next_x_line(&self) -> Ray {
-		let delta_x = self.distance_to_next_grid_line(Axis::X);
-		let delta_y = self.angle.tan() * delta_x;
-		return self.grow_with_delta(delta_x, delta_y);
+		self.end = Point {x: self.start.x + self.direction.x * self.length, y: self.start.y + self.direction.y * self.length};
 	}
 
-	/// Creates a new ray where the end point progressed to the next grid line that is parallel to the Y axis.
-	fn grow_to_next_y_line(&self) -> Ray {
-		let delta_y = self.distance_to_next_grid_line(Axis::Y);
-		let delta_x = delta_y / self.angle.tan();
-		return self.grow_with_delta(delta_x, delta_y);
+	/// Determines how the ray traverses the grid lines crossing a single axis.
+	/// Returns the tile step, the ray length between two grid lines and the ray length up to the first grid line.
+	///
+	/// # Parameters:
+	///		- position:		The component of the ray's start on the axis.
+	///		- direction:	The component of the ray's direction on the axis.
+	fn traversal_for_component(position: f64, direction: f64) -> (isize, f64, f64) {
+		// A ray that is parallel to the grid lines never crosses any of them. Axis-aligned angles leave tiny rounding errors in the direction
+		// (e.g. `cos(3π/2)` is slightly negative), which would let a ray starting on a grid line drift into the neighboring tiles.
+		if direction.abs() < Ray::EPSILON {
+			return (0, f64::INFINITY, f64::INFINITY);
+		}
+
+		let delta = (1.0 / direction).abs();
+
+		if direction > 0.0 {
+			(1, delta, (position.floor() + 1.0 - position) * delta)
+		}
+		else {
+			(-1, delta, (position - position.floor()) * delta)
+		}
 	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use game::map::Map;
+	use game::map::Tile;
+	use proptest::prelude::*;
+	use std::f64::consts::FRAC_PI_2;
+	use std::f64::consts::PI;
 
-	/// Moves the end point of the ray by the given delta.
-	fn grow_with_delta(&self, delta_x: f64, delta_y: f64) -> Ray {
-		Ray::new_with_end(self.start.clone(), Point {x: self.end.x + delta_x, y: self.end.y + delta_y}, self.angle)
+	/// The tolerance for comparing coordinates that were computed along a ray
+	const TOLERANCE: f64 = 1e-9;
+
+	fn is_on_grid_line(value: f64) -> bool {
+		(value - value.round()).abs() < TOLERANCE * value.abs().max(1.0)
 	}
 
-	/// Determines the shorted distance between the ray's endpoint and the next grid line that is parallel to the given axis and in the ray's direction.
-	///
-	/// # Parameters:
-	///		- axis:		The axis the matching grid line should be parallel to
-	fn distance_to_next_grid_line(&self, axis: Axis) -> f64 {
-		let position = self.end.component(&axis);
-		match Direction::from_angle(&self.angle, &axis) {
-			Direction::Increasing => (position).floor() + 1.0 - position,
-			Direction::Decreasing => position.ceil() - 1.0 - position
+	proptest! {
+		#[test]
+		fn grow_lands_on_grid_line(x in -50.0..50.0f64, y in -50.0..50.0f64, angle in -2.0 * PI..2.0 * PI) {
+			let mut ray = Ray::new(Point {x, y}, angle);
+
+			for _ in 0..50 {
+				ray.grow();
+				match ray.face {
+					Some(Face::East) | Some(Face::West) => prop_assert!(is_on_grid_line(ray.end.x), "x = {}", ray.end.x),
+					Some(Face::North) | Some(Face::South) => prop_assert!(is_on_grid_line(ray.end.y), "y = {}", ray.end.y),
+					None => prop_assert!(false, "the ray did not leave its starting tile")
+				}
+			}
+		}
+
+		#[test]
+		fn length_is_monotonic(x in -50.0..50.0f64, y in -50.0..50.0f64, angle in -2.0 * PI..2.0 * PI) {
+			let mut ray = Ray::new(Point {x, y}, angle);
+			let mut previous_length = ray.length;
+
+			for _ in 0..50 {
+				ray.grow();
+				prop_assert!(ray.length >= previous_length);
+
+				// The length matches the distance between start and end
+				let distance = (ray.end.x - x).hypot(ray.end.y - y);
+				prop_assert!((distance - ray.length).abs() < TOLERANCE * ray.length.max(1.0));
+				previous_length = ray.length;
+			}
+		}
+
+		#[test]
+		fn tiles_are_adjacent_and_touch_end(x in -50.0..50.0f64, y in -50.0..50.0f64, angle in -2.0 * PI..2.0 * PI) {
+			let mut ray = Ray::new(Point {x, y}, angle);
+			prop_assert_eq!(ray.tile.clone(), TilePosition::containing(&ray.start));
+
+			for _ in 0..50 {
+				let previous_tile = ray.tile.clone();
+				ray.grow();
+
+				// Each step enters a tile sharing a face with the previous one
+				prop_assert_eq!((ray.tile.x - previous_tile.x).abs() + (ray.tile.y - previous_tile.y).abs(), 1);
+
+				// The end point lies on the border of the entered tile
+				let tolerance = TOLERANCE * ray.length.max(1.0) + TOLERANCE;
+				prop_assert!(ray.end.x >= ray.tile.x as f64 - tolerance && ray.end.x <= (ray.tile.x + 1) as f64 + tolerance);
+				prop_assert!(ray.end.y >= ray.tile.y as f64 - tolerance && ray.end.y <= (ray.tile.y + 1) as f64 + tolerance);
+			}
+		}
+
+		#[test]
+		fn axis_aligned_ray_on_grid_line_stays_in_column(x in -50i32..50, y in -50.0..50.0f64, quarter in -8i32..8) {
+			// A vertical ray running along a grid line must not drift into the neighboring column of tiles
+			let angle = (2 * quarter + 1) as f64 * FRAC_PI_2;
+			let mut ray = Ray::new(Point {x: x as f64, y}, angle);
+
+			for _ in 0..50 {
+				ray.grow();
+				prop_assert_eq!(ray.tile.x, x as isize);
+				prop_assert!(ray.length > 0.0);
+			}
+		}
+
+		#[test]
+		fn ray_reports_distance_to_wall(start_x in 1.0..2.0f64, start_y in 1.0..2.0f64, wall in 3usize..40, angle in -1.0..1.0f64) {
+			// A vertical wall at a known column of an otherwise open map
+			let height = 200;
+			let row = format!("{}R", " ".repeat(wall));
+			let map = Map::new(&vec![row.as_str(); height].join("\n"));
+			let start = Point {x: start_x, y: start_y + height as f64 / 2.0};
+
+			let mut ray = Ray::new(start, angle);
+			while ray.length <= map.max_distance() as f64 {
+				ray.grow();
+				if let Tile::Wall(_) = map.tile(&ray.tile) {
+					break;
+				}
+			}
+
+			let expected_length = (wall as f64 - start_x) / angle.cos();
+			prop_assert_eq!(ray.tile.x, wall as isize);
+			prop_assert_eq!(ray.face, Some(Face::West));
+			prop_assert!((ray.length - expected_length).abs() < TOLERANCE * expected_length, "{} != {}", ray.length, expected_length);
 		}
 	}
 }
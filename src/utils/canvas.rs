@@ -0,0 +1,379 @@
+use std::ops::Range;
+use utils::color::RGBColor;
+use utils::font;
+use utils::image::Image;
+use utils::pixel_format::PixelFormat;
+
+/// A buffer for pixel drawing. A canvas may be restricted to a range of columns of the underlying buffer, so that several
+/// canvases can draw to disjoint parts of the same buffer at the same time.
+pub struct Canvas<'a> {
+	/// The rows of the buffer. Each row starts at the first column of `columns`.
+	rows: Vec<&'a mut [u8]>,
+	columns: Range<usize>,
+	height: usize,
+	width: usize,
+
+	/// The memory layout of the buffer's pixels
+	format: PixelFormat
+}
+
+impl<'a> Canvas<'a> {
+	/// Creates a new drawing buffer for with the given height and width.
+	///
+	/// # Parameters:
+	///		- buffer:			The pixels of the buffer, row by row
+	///		- pitch:			The number of bytes between the starts of two rows
+	///		- width, height:	The size of the buffer in pixels
+	///		- format:			The memory layout of the buffer's pixels
+	pub fn new(buffer: &'a mut [u8], pitch: usize, width: usize, height: usize, format: PixelFormat) -> Canvas<'a> {
+		let rows = buffer
+			.chunks_mut(pitch)
+			.take(height)
+			.map(|row| &mut row[..width * format.bytes_per_pixel()])
+			.collect();
+
+		Canvas {rows, columns: 0..width, width, height, format}
+	}
+
+	/// The width of the canvas
+	pub fn width(&self) -> usize {
+		self.width
+	}
+
+	/// The height of the canvas
+	pub fn height(&self) -> usize {
+		self.height
+	}
+
+	/// The columns of the canvas that can be drawn to
+	pub fn columns(&self) -> Range<usize> {
+		self.columns.clone()
+	}
+
+	/// The memory layout of the canvas' pixels
+	pub fn format(&self) -> PixelFormat {
+		self.format
+	}
+
+	/// Draws a pixel at the given coordinates. The column must be inside of `columns()`.
+	#[inline]
+	pub fn draw_pixel(&mut self, x: usize, y: usize, color: &RGBColor) {
+		let bytes_per_pixel = self.format.bytes_per_pixel();
+		let offset = (x - self.columns.start) * bytes_per_pixel;
+		let encoded = self.format.encode(color);
+		Canvas::write_pixel(self.rows[y], offset, &encoded, bytes_per_pixel);
+	}
+
+	/// Returns the color of the pixel at the given coordinates. The column must be inside of `columns()`.
+	#[inline]
+	pub fn pixel(&self, x: usize, y: usize) -> RGBColor {
+		let offset = (x - self.columns.start) * self.format.bytes_per_pixel();
+		self.format.decode(&self.rows[y][offset..])
+	}
+
+	/// Fills a range of pixels within a row. The color is only converted once, so this is much faster than drawing each pixel.
+	///
+	/// # Parameters:
+	///		- y:		The row to be filled
+	///		- columns:	The columns to be filled. Must be inside of `columns()`.
+	///		- color:	The fill color
+	#[inline]
+	pub fn fill_row(&mut self, y: usize, columns: Range<usize>, color: &RGBColor) {
+		let bytes_per_pixel = self.format.bytes_per_pixel();
+		let encoded = self.format.encode(color);
+		let start = (columns.start - self.columns.start) * bytes_per_pixel;
+		let end = (columns.end - self.columns.start) * bytes_per_pixel;
+		let row = &mut self.rows[y][start..end];
+
+		match bytes_per_pixel {
+			3 => row.chunks_exact_mut(3).for_each(|pixel| pixel.copy_from_slice(&encoded[..3])),
+			_ => row.chunks_exact_mut(4).for_each(|pixel| pixel.copy_from_slice(&encoded))
+		}
+	}
+
+	/// Fills a range of pixels within a column. The color is only converted once, so this is much faster than drawing each pixel.
+	///
+	/// # Parameters:
+	///		- x:		The column to be filled. Must be inside of `columns()`.
+	///		- rows:		The rows to be filled
+	///		- color:	The fill color
+	#[inline]
+	pub fn fill_column(&mut self, x: usize, rows: Range<usize>, color: &RGBColor) {
+		let bytes_per_pixel = self.format.bytes_per_pixel();
+		let encoded = self.format.encode(color);
+		let offset = (x - self.columns.start) * bytes_per_pixel;
+
+		for row in self.rows[rows].iter_mut() {
+			Canvas::write_pixel(row, offset, &encoded, bytes_per_pixel);
+		}
+	}
+
+	/// Copies encoded pixels into a range of pixels within a column, one pixel per row. Pixels that are drawn to many columns (e.g. a
+	/// gradient) only have to be converted once, see `PixelFormat::encode`.
+	///
+	/// # Parameters:
+	///		- x:		The column to be drawn to. Must be inside of `columns()`.
+	///		- rows:		The rows to be drawn to
+	///		- pixels:	The pixels encoded in the canvas' format, one for each row of `rows`
+	#[inline]
+	pub fn copy_column(&mut self, x: usize, rows: Range<usize>, pixels: &[[u8; 4]]) {
+		let bytes_per_pixel = self.format.bytes_per_pixel();
+		let offset = (x - self.columns.start) * bytes_per_pixel;
+
+		for (row, encoded) in self.rows[rows].iter_mut().zip(pixels) {
+			Canvas::write_pixel(row, offset, encoded, bytes_per_pixel);
+		}
+	}
+
+	/// Places a color with the given opacity on top of a range of pixels within a row. The color is only converted once.
+	///
+	/// # Parameters:
+	///		- y:		The row to be blended
+	///		- columns:	The columns to be blended. Must be inside of `columns()`.
+	///		- color:	The color to be placed on top of the pixels
+	///		- alpha:	The opacity of the color (0: transparent, 255: opaque)
+	pub fn blend_row(&mut self, y: usize, columns: Range<usize>, color: &RGBColor, alpha: u8) {
+		let bytes_per_pixel = self.format.bytes_per_pixel();
+		let format = self.format;
+		let start = (columns.start - self.columns.start) * bytes_per_pixel;
+		let end = (columns.end - self.columns.start) * bytes_per_pixel;
+
+		for pixel in self.rows[y][start..end].chunks_exact_mut(bytes_per_pixel) {
+			let encoded = format.encode(&format.decode(pixel).blend(color, alpha));
+			pixel.copy_from_slice(&encoded[..bytes_per_pixel]);
+		}
+	}
+
+	/// Writes an encoded pixel into a row. The copied sizes are constant, so the compiler can turn them into single stores.
+	#[inline(always)]
+	fn write_pixel(row: &mut [u8], offset: usize, encoded: &[u8; 4], bytes_per_pixel: usize) {
+		match bytes_per_pixel {
+			3 => row[offset..(offset + 3)].copy_from_slice(&encoded[..3]),
+			_ => row[offset..(offset + 4)].copy_from_slice(encoded)
+		}
+	}
+
+	/// Places a color with the given opacity on top of the pixel at the given coordinates. The column must be inside of `columns()`.
+	///
+	/// # Parameters:
+	///		- x, y:		The coordinates of the pixel
+	///		- color:	The color to be placed on top of the pixel
+	///		- alpha:	The opacity of the color (0: transparent, 255: opaque)
+	pub fn blend_pixel(&mut self, x: usize, y: usize, color: &RGBColor, alpha: u8) {
+		let blended_color = self.pixel(x, y).blend(color, alpha);
+		self.draw_pixel(x, y, &blended_color);
+	}
+
+	/// Draws a filled rectangle. Parts of the rectangle outside of the drawable area are clipped.
+	///
+	/// # Parameters:
+	///		- x, y:				The top left corner of the rectangle
+	///		- width, height:	The size of the rectangle
+	///		- color:			The fill color
+	pub fn fill_rect(&mut self, x: isize, y: isize, width: usize, height: usize, color: &RGBColor) {
+		let (columns, rows) = self.clip_rect(x, y, width, height);
+		for row in rows {
+			self.fill_row(row, columns.clone(), color);
+		}
+	}
+
+	/// Draws a translucent filled rectangle. Parts of the rectangle outside of the drawable area are clipped.
+	///
+	/// # Parameters:
+	///		- x, y:				The top left corner of the rectangle
+	///		- width, height:	The size of the rectangle
+	///		- color:			The fill color
+	///		- alpha:			The opacity of the fill color (0: transparent, 255: opaque)
+	pub fn blend_rect(&mut self, x: isize, y: isize, width: usize, height: usize, color: &RGBColor, alpha: u8) {
+		let (columns, rows) = self.clip_rect(x, y, width, height);
+		for row in rows {
+			self.blend_row(row, columns.clone(), color, alpha);
+		}
+	}
+
+	/// Determines the columns and rows of a rectangle inside the drawable area. The ranges are empty if the rectangle is outside of it.
+	fn clip_rect(&self, x: isize, y: isize, width: usize, height: usize) -> (Range<usize>, Range<usize>) {
+		let left = x.clamp(self.columns.start as isize, self.columns.end as isize) as usize;
+		let right = (x + width as isize).clamp(left as isize, self.columns.end as isize) as usize;
+		let top = y.clamp(0, self.height as isize) as usize;
+		let bottom = (y + height as isize).clamp(top as isize, self.height as isize) as usize;
+
+		(left..right, top..bottom)
+	}
+
+	/// Draws an image using its alpha channel. Parts of the image outside of the drawable area are clipped.
+	///
+	/// # Parameters:
+	///		- x, y:		The top left corner of the image
+	///		- image:	The image to be drawn
+	///		- scale:	The size of an image pixel in canvas pixels
+	pub fn draw_image(&mut self, x: isize, y: isize, image: &Image, scale: usize) {
+		let scale = scale.max(1);
+
+		for image_y in 0..image.height() {
+			for image_x in 0..image.width() {
+				let (color, alpha) = image.pixel(image_x, image_y);
+				let (left, top) = (x + (image_x * scale) as isize, y + (image_y * scale) as isize);
+
+				// Opaque pixels don't have to be blended with the canvas
+				match alpha {
+					0 => {},
+					255 => self.fill_rect(left, top, scale, scale, color),
+					_ => self.blend_rect(left, top, scale, scale, color, alpha)
+				}
+			}
+		}
+	}
+
+	/// Draws text using the built-in bitmap font. Lines are separated by line breaks. Parts of the text outside of the drawable area are clipped.
+	///
+	/// # Parameters:
+	///		- x, y:		The top left corner of the text
+	///		- text:		The text to be drawn
+	///		- color:	The text color
+	///		- scale:	The size of a font pixel in canvas pixels
+	pub fn draw_text(&mut self, x: isize, y: isize, text: &str, color: &RGBColor, scale: usize) {
+		let scale = scale.max(1);
+
+		for (line_index, line) in text.lines().enumerate() {
+			let line_top = y + (line_index * font::LINE_HEIGHT * scale) as isize;
+
+			for (character_index, character) in line.chars().enumerate() {
+				let glyph_left = x + (character_index * font::GLYPH_ADVANCE * scale) as isize;
+
+				for (glyph_y, glyph_row) in font::glyph(character).iter().enumerate() {
+					for glyph_x in 0..font::GLYPH_WIDTH {
+						if glyph_row & (1 << (font::GLYPH_WIDTH - 1 - glyph_x)) != 0 {
+							self.fill_rect(glyph_left + (glyph_x * scale) as isize, line_top + (glyph_y * scale) as isize, scale, scale, color);
+						}
+					}
+				}
+			}
+		}
+	}
+
+	/// Determines the size of a text drawn by `draw_text`.
+	///
+	/// # Parameters:
+	///		- text:		The text to be measured
+	///		- scale:	The size of a font pixel in canvas pixels
+	pub fn text_size(text: &str, scale: usize) -> (usize, usize) {
+		let scale = scale.max(1);
+		let columns = text.lines().map(|line| line.chars().count()).max().unwrap_or(0);
+		let lines = text.lines().count();
+
+		let width = (columns * font::GLYPH_ADVANCE).saturating_sub(1) * scale;
+		let height = (lines * font::LINE_HEIGHT).saturating_sub(font::LINE_HEIGHT - font::GLYPH_HEIGHT) * scale;
+		(width, height)
+	}
+
+	/// Draws a line between two points using Bresenham's algorithm. Parts of the line outside of the drawable area are clipped.
+	/// See: https://en.wikipedia.org/wiki/Bresenham%27s_line_algorithm
+	///
+	/// # Parameters:
+	///		- from:		The starting point of the line
+	///		- to:		The end point of the line
+	///		- color:	The line color
+	pub fn draw_line(&mut self, from: (isize, isize), to: (isize, isize), color: &RGBColor) {
+		let delta_x = (to.0 - from.0).abs();
+		let delta_y = -(to.1 - from.1).abs();
+		let step_x = if from.0 < to.0 { 1 } else { -1 };
+		let step_y = if from.1 < to.1 { 1 } else { -1 };
+
+		let (mut x, mut y) = from;
+		let mut error = delta_x + delta_y;
+
+		loop {
+			if self.is_drawable(x, y) {
+				self.draw_pixel(x as usize, y as usize, color);
+			}
+
+			if x == to.0 && y == to.1 {
+				break;
+			}
+
+			let doubled_error = 2 * error;
+			if doubled_error >= delta_y {
+				error += delta_y;
+				x += step_x;
+			}
+			if doubled_error <= delta_x {
+				error += delta_x;
+				y += step_y;
+			}
+		}
+	}
+
+	/// Determines whether the given coordinates are inside the drawable area of the canvas.
+	pub fn is_drawable(&self, x: isize, y: isize) -> bool {
+		x >= self.columns.start as isize && x < self.columns.end as isize && y >= 0 && y < self.height as isize
+	}
+
+	/// Returns a canvas for drawing to a range of columns of this canvas. In contrast to `split_columns`, the new canvas has its own
+	/// coordinate system: its width is the number of columns and its first column is the first column of the range.
+	///
+	/// # Parameters:
+	///		- columns:	The columns of this canvas the new canvas draws to. Must be inside of `columns()`.
+	pub fn sub_canvas(&mut self, columns: Range<usize>) -> Canvas<'_> {
+		let bytes_per_pixel = self.format.bytes_per_pixel();
+		let start = (columns.start - self.columns.start) * bytes_per_pixel;
+		let end = (columns.end - self.columns.start) * bytes_per_pixel;
+		let rows = self.rows.iter_mut().map(|row| &mut row[start..end]).collect();
+
+		Canvas {rows, columns: 0..columns.len(), width: columns.len(), height: self.height, format: self.format}
+	}
+
+	/// Splits the drawable columns of the canvas into the given number of strips of (almost) equal width. Each strip is a canvas of
+	/// the same size that can only draw to its own columns, so the strips can be drawn to in parallel.
+	///
+	/// # Parameters:
+	///		- count:	The number of strips. Fewer strips are returned if there are less columns than strips.
+	pub fn split_columns(&mut self, count: usize) -> Vec<Canvas<'_>> {
+		let column_count = self.columns.len();
+		let strip_width = column_count.div_ceil(count.max(1));
+
+		// Determine the columns of each strip.
+		let mut strips: Vec<Canvas> = (0..column_count)
+			.step_by(strip_width.max(1))
+			.map(|offset| {
+				let start = self.columns.start + offset;
+				let end = (start + strip_width).min(self.columns.end);
+				Canvas {rows: Vec::with_capacity(self.height), columns: start..end, width: self.width, height: self.height, format: self.format}
+			})
+			.collect();
+
+		// Cut each row into disjoint slices for the strips.
+		let bytes_per_pixel = self.format.bytes_per_pixel();
+		for row in self.rows.iter_mut() {
+			let mut remainder: &mut [u8] = row;
+
+			for strip in strips.iter_mut() {
+				let (strip_row, next_remainder) = remainder.split_at_mut(strip.columns.len() * bytes_per_pixel);
+				strip.rows.push(strip_row);
+				remainder = next_remainder;
+			}
+		}
+
+		strips
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn fills_and_blends_clipped_spans() {
+		let mut buffer = vec![0u8; 4 * 3 * 4];
+		let mut canvas = Canvas::new(&mut buffer, 4 * 4, 4, 3, PixelFormat::BGRA8888);
+
+		let gradient: Vec<[u8; 4]> = [RGBColor::red(), RGBColor::green(), RGBColor::blue()].iter().map(|color| PixelFormat::BGRA8888.encode(color)).collect();
+		canvas.copy_column(1, 1..3, &gradient);
+		assert_eq!((canvas.pixel(1, 0), canvas.pixel(1, 1), canvas.pixel(1, 2)), (RGBColor::black(), RGBColor::red(), RGBColor::green()));
+
+		canvas.fill_rect(-2, 1, 4, 10, &RGBColor::white());
+		canvas.blend_rect(3, -1, 5, 2, &RGBColor::white(), 255);
+		assert_eq!((canvas.pixel(0, 0), canvas.pixel(0, 2), canvas.pixel(1, 1), canvas.pixel(2, 1)), (RGBColor::black(), RGBColor::white(), RGBColor::white(), RGBColor::black()));
+		assert_eq!((canvas.pixel(3, 0), canvas.pixel(3, 1)), (RGBColor::white(), RGBColor::black()));
+	}
+}
@@ -1,3 +1,5 @@
+use std::fmt;
+
 /// Represents a RGB color
 #[derive(PartialEq, Eq, Clone, Debug)]
 pub struct RGBColor {
@@ -13,12 +15,24 @@ pub struct RGBColor {
 
 impl RGBColor {
 	pub fn black() -> RGBColor { RGBColor {red: 0, green: 0, blue: 0} }
+	pub fn white() -> RGBColor { RGBColor {red: 255, green: 255, blue: 255} }
 	pub fn dark_gray() -> RGBColor { RGBColor {red: 50, green: 50, blue: 50} }
+	pub fn light_gray() -> RGBColor { RGBColor {red: 180, green: 180, blue: 180} }
 	pub fn red() -> RGBColor { RGBColor {red: 180, green: 0, blue: 0} }
 	pub fn green() -> RGBColor { RGBColor {red: 50, green: 128, blue: 0} }
 	pub fn blue() -> RGBColor { RGBColor {red: 0, green: 64, blue: 128} }
 	pub fn yellow() -> RGBColor { RGBColor {red: 255, green: 184, blue: 0} }
 	pub fn orange() -> RGBColor { RGBColor {red: 255, green: 80, blue: 0} }
+
+	/// Parses a color in hexadecimal notation (e.g. "#ff8000"), as written by `Display`.
+	pub fn parse(text: &str) -> Option<RGBColor> {
+		if text.len() != 7 || !text.starts_with('#') || !text[1..].chars().all(|character| character.is_ascii_hexdigit()) {
+			return None;
+		}
+
+		let component = |index: usize| text.get(index..index + 2).and_then(|digits| u8::from_str_radix(digits, 16).ok());
+		Some(RGBColor {red: component(1)?, green: component(3)?, blue: component(5)?})
+	}
 }
 
 impl RGBColor {
@@ -31,9 +45,109 @@ impl RGBColor {
 		}
 	}
 
+	/// Creates a new color lit by colored light. Each component is multiplied by the corresponding component of the light.
+	pub fn tint(&self, light: &LightColor) -> RGBColor {
+		RGBColor {
+			red:	RGBColor::tint_component(self.red, light.red),
+			green:	RGBColor::tint_component(self.green, light.green),
+			blue:	RGBColor::tint_component(self.blue, light.blue)
+		}
+	}
+
+	/// Creates a new color by placing the given color with the given opacity on top of this color.
+	///
+	/// # Parameters:
+	///		- color:	The color placed on top of this color
+	///		- alpha:	The opacity of the color on top (0: transparent, 255: opaque)
+	pub fn blend(&self, color: &RGBColor, alpha: u8) -> RGBColor {
+		RGBColor {
+			red:	RGBColor::blend_component(self.red, color.red, alpha),
+			green:	RGBColor::blend_component(self.green, color.green, alpha),
+			blue:	RGBColor::blend_component(self.blue, color.blue, alpha)
+		}
+	}
+
+	/// Determines the value of a certain color component when blending two colors.
+	fn blend_component(background: u8, foreground: u8, alpha: u8) -> u8 {
+		((background as u32 * (255 - alpha as u32) + foreground as u32 * alpha as u32) / 255) as u8
+	}
+
 	/// Determines the value of a certain color component when darkening a color.
 	fn darken_component(component: u8, percentage: f64) -> u8 {
 		(((component as f64) * percentage).max(0.0) as u8).min(component)
 	}
 
+	/// Determines the value of a certain color component when lighting a color. Overexposed components are limited to their maximum.
+	fn tint_component(component: u8, factor: f64) -> u8 {
+		((component as f64) * factor).clamp(0.0, 255.0) as u8
+	}
+
+}
+
+/// The color and brightness of light falling onto a surface. The components are factors for the components of the surface's color
+/// (0: dark, 1: the surface's own color, above 1: brighter than the surface's own color).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LightColor {
+	pub red: f64,
+	pub green: f64,
+	pub blue: f64
+}
+
+impl LightColor {
+	/// No light at all
+	pub fn dark() -> LightColor {
+		LightColor {red: 0.0, green: 0.0, blue: 0.0}
+	}
+
+	/// White light of a given brightness (1: surfaces appear in their own color)
+	pub fn white(brightness: f64) -> LightColor {
+		LightColor {red: brightness, green: brightness, blue: brightness}
+	}
+
+	/// Light of a color and a brightness. At a brightness of 1, white light lets surfaces appear in their own color.
+	pub fn new(color: &RGBColor, brightness: f64) -> LightColor {
+		let factor = brightness / 255.0;
+		LightColor {red: color.red as f64 * factor, green: color.green as f64 * factor, blue: color.blue as f64 * factor}
+	}
+
+	/// Combines two lights falling onto the same surface.
+	pub fn add(&self, light: &LightColor) -> LightColor {
+		LightColor {red: self.red + light.red, green: self.green + light.green, blue: self.blue + light.blue}
+	}
+
+	/// Changes the brightness of the light by a factor.
+	pub fn scale(&self, factor: f64) -> LightColor {
+		LightColor {red: self.red * factor, green: self.green * factor, blue: self.blue * factor}
+	}
+}
+
+impl fmt::Display for RGBColor {
+	fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		write!(formatter, "#{:02x}{:02x}{:02x}", self.red, self.green, self.blue)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn tints_colors_by_light() {
+		let color = RGBColor {red: 200, green: 100, blue: 50};
+		let light = LightColor::new(&RGBColor::orange(), 1.0).add(&LightColor::white(0.5));
+
+		assert_eq!(color.tint(&LightColor::white(0.5)), color.adjust_light_intensity(0.5));
+		assert_eq!(color.tint(&LightColor::dark()), RGBColor::black());
+		assert_eq!(color.tint(&light), RGBColor {red: 255, green: 81, blue: 25});
+	}
+
+	#[test]
+	fn parses_written_colors() {
+		assert_eq!(RGBColor::parse("#ff8000"), Some(RGBColor {red: 255, green: 128, blue: 0}));
+		assert_eq!(RGBColor::parse(&RGBColor::yellow().to_string()), Some(RGBColor::yellow()));
+
+		for text in ["ff8000", "#ff800", "#ff80000", "#gg8000", "#+f8000"].iter() {
+			assert_eq!(RGBColor::parse(text), None, "{}", text);
+		}
+	}
 }
@@ -0,0 +1,121 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+
+/// Settings loaded from a configuration file. The file consists of `key = value` lines, empty lines and comments starting with `#`.
+/// Keys are grouped by a prefix (e.g. `window.size`).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Config {
+	values: BTreeMap<String, String>
+}
+
+impl Config {
+	/// Creates an empty configuration.
+	pub fn new() -> Config {
+		Config {values: BTreeMap::new()}
+	}
+
+	/// Parses the content of a configuration file.
+	pub fn parse(text: &str) -> Result<Config, String> {
+		let mut config = Config::new();
+
+		for (index, line) in text.lines().enumerate() {
+			let line = line.trim();
+			if line.is_empty() || line.starts_with('#') {
+				continue;
+			}
+
+			match line.find('=') {
+				Some(separator) if separator > 0 => config.set(line[..separator].trim(), line[separator + 1 ..].trim()),
+				_ => return Err(format!("Line {}: Expected \"key = value\", found \"{}\"", index + 1, line))
+			}
+		}
+
+		Ok(config)
+	}
+
+	/// Loads a configuration file. A missing file is treated like an empty file.
+	pub fn load(path: &str) -> Result<Config, String> {
+		match fs::read_to_string(path) {
+			Ok(text) => Config::parse(&text).map_err(|error| format!("{}: {}", path, error)),
+			Err(ref error) if error.kind() == io::ErrorKind::NotFound => Ok(Config::new()),
+			Err(error) => Err(format!("Could not read {}: {}", path, error))
+		}
+	}
+
+	/// Writes the configuration to a file, one `key = value` line per key in alphabetical order. Comments of an existing file are not
+	/// kept.
+	pub fn save(&self, path: &str) -> Result<(), String> {
+		fs::write(path, self.to_string()).map_err(|error| format!("Could not write {}: {}", path, error))
+	}
+
+	/// Returns the value of a key, if it is set.
+	pub fn get(&self, key: &str) -> Option<&str> {
+		self.values.get(key).map(|value| value.as_str())
+	}
+
+	/// Sets the value of a key.
+	pub fn set(&mut self, key: &str, value: &str) {
+		self.values.insert(key.to_string(), value.to_string());
+	}
+
+	/// Converts the value of a key. Returns `None` if the key is not set and an error if the value is invalid.
+	///
+	/// # Parameters:
+	///		- key:			The key of the value
+	///		- conversion:	Converts the value, returns `None` for invalid values
+	pub fn convert<T, F>(&self, key: &str, conversion: F) -> Result<Option<T>, String> where F: Fn(&str) -> Option<T> {
+		match self.get(key) {
+			Some(value) => conversion(value).map(Some).ok_or_else(|| format!("Invalid value for {}: {}", key, value)),
+			None => Ok(None)
+		}
+	}
+}
+
+impl fmt::Display for Config {
+	fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		for (key, value) in self.values.iter() {
+			writeln!(formatter, "{} = {}", key, value)?;
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parses_keys_values_and_comments() {
+		let config = Config::parse("# Window\nwindow.size = 1024x768\n\n  window.title=A = B  \n").unwrap();
+
+		assert_eq!(config.get("window.size"), Some("1024x768"));
+		assert_eq!(config.get("window.title"), Some("A = B"));
+		assert_eq!(config.get("window.vsync"), None);
+	}
+
+	#[test]
+	fn parses_written_configuration() {
+		let mut config = Config::new();
+		config.set("window.title", "Ray Casting = Fun");
+		config.set("renderer.field_of_view", "67");
+
+		assert_eq!(config.to_string(), "renderer.field_of_view = 67\nwindow.title = Ray Casting = Fun\n");
+		assert_eq!(Config::parse(&config.to_string()), Ok(config));
+	}
+
+	#[test]
+	fn rejects_lines_without_key() {
+		assert!(Config::parse("window.size = 1024x768\nfullscreen").unwrap_err().starts_with("Line 2:"));
+		assert!(Config::parse("= 1024x768").is_err());
+	}
+
+	#[test]
+	fn reports_invalid_values() {
+		let config = Config::parse("window.display = two").unwrap();
+
+		assert_eq!(config.convert("window.display", |value| value.parse::<u32>().ok()), Err("Invalid value for window.display: two".to_string()));
+		assert_eq!(config.convert("window.vsync", |value| value.parse::<u32>().ok()), Ok(None));
+	}
+}
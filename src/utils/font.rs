@@ -0,0 +1,132 @@
+//
+// A small bitmap font for drawing text without any external resources.
+//
+// The glyphs are taken from the public domain 5x7 font of the X11 "misc-fixed" collection by Markus Kuhn.
+//
+
+/// The width of a glyph in pixels
+pub const GLYPH_WIDTH: usize = 5;
+
+/// The height of a glyph in pixels
+pub const GLYPH_HEIGHT: usize = 7;
+
+/// The horizontal distance between the start of two glyphs
+pub const GLYPH_ADVANCE: usize = GLYPH_WIDTH + 1;
+
+/// The vertical distance between two lines of text
+pub const LINE_HEIGHT: usize = GLYPH_HEIGHT + 2;
+
+/// Returns the bitmap of a character. Each row is stored in the lower five bits of a byte, whereas bit 4 is the leftmost pixel.
+/// Characters outside of printable ASCII are drawn as a question mark.
+pub fn glyph(character: char) -> &'static [u8; GLYPH_HEIGHT] {
+	let code = character as usize;
+
+	if code >= FIRST_CHARACTER && code < FIRST_CHARACTER + GLYPHS.len() {
+		&GLYPHS[code - FIRST_CHARACTER]
+	}
+	else {
+		&GLYPHS['?' as usize - FIRST_CHARACTER]
+	}
+}
+
+/// The character of the first glyph
+const FIRST_CHARACTER: usize = 32;
+
+/// The glyphs of all printable ASCII characters
+const GLYPHS: [[u8; GLYPH_HEIGHT]; 95] = [
+	[0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000],	// space
+	[0b00100, 0b00100, 0b00100, 0b00100, 0b00000, 0b00100, 0b00000],	// !
+	[0b01010, 0b01010, 0b01010, 0b00000, 0b00000, 0b00000, 0b00000],	// "
+	[0b00000, 0b01010, 0b11111, 0b01010, 0b11111, 0b01010, 0b00000],	// #
+	[0b00000, 0b01110, 0b10100, 0b01110, 0b00101, 0b01110, 0b00000],	// $
+	[0b10000, 0b10010, 0b00100, 0b01000, 0b10010, 0b00010, 0b00000],	// %
+	[0b00000, 0b01000, 0b10100, 0b01000, 0b10100, 0b01010, 0b00000],	// &
+	[0b00100, 0b00100, 0b00100, 0b00000, 0b00000, 0b00000, 0b00000],	// apostrophe
+	[0b00100, 0b01000, 0b01000, 0b01000, 0b01000, 0b00100, 0b00000],	// (
+	[0b01000, 0b00100, 0b00100, 0b00100, 0b00100, 0b01000, 0b00000],	// )
+	[0b00000, 0b01010, 0b00100, 0b01110, 0b00100, 0b01010, 0b00000],	// *
+	[0b00000, 0b00100, 0b00100, 0b11111, 0b00100, 0b00100, 0b00000],	// +
+	[0b00000, 0b00000, 0b00000, 0b00000, 0b00110, 0b00100, 0b01000],	// ,
+	[0b00000, 0b00000, 0b00000, 0b11110, 0b00000, 0b00000, 0b00000],	// -
+	[0b00000, 0b00000, 0b00000, 0b00000, 0b01100, 0b01100, 0b00000],	// .
+	[0b00000, 0b00010, 0b00100, 0b01000, 0b10000, 0b00000, 0b00000],	// /
+	[0b00100, 0b01010, 0b01010, 0b01010, 0b01010, 0b00100, 0b00000],	// 0
+	[0b00100, 0b01100, 0b00100, 0b00100, 0b00100, 0b01110, 0b00000],	// 1
+	[0b01100, 0b10010, 0b00010, 0b00100, 0b01000, 0b11110, 0b00000],	// 2
+	[0b11110, 0b00010, 0b01100, 0b00010, 0b10010, 0b01100, 0b00000],	// 3
+	[0b00100, 0b01100, 0b10100, 0b11110, 0b00100, 0b00100, 0b00000],	// 4
+	[0b11110, 0b10000, 0b11100, 0b00010, 0b10010, 0b01100, 0b00000],	// 5
+	[0b01100, 0b10000, 0b11100, 0b10010, 0b10010, 0b01100, 0b00000],	// 6
+	[0b11110, 0b00010, 0b00100, 0b00100, 0b01000, 0b01000, 0b00000],	// 7
+	[0b01100, 0b10010, 0b01100, 0b10010, 0b10010, 0b01100, 0b00000],	// 8
+	[0b01100, 0b10010, 0b10010, 0b01110, 0b00010, 0b01100, 0b00000],	// 9
+	[0b00000, 0b01100, 0b01100, 0b00000, 0b01100, 0b01100, 0b00000],	// :
+	[0b00000, 0b01100, 0b01100, 0b00000, 0b01100, 0b01000, 0b10000],	// ;
+	[0b00000, 0b00010, 0b00100, 0b01000, 0b00100, 0b00010, 0b00000],	// <
+	[0b00000, 0b00000, 0b11110, 0b00000, 0b11110, 0b00000, 0b00000],	// =
+	[0b00000, 0b01000, 0b00100, 0b00010, 0b00100, 0b01000, 0b00000],	// >
+	[0b00100, 0b01010, 0b00010, 0b00100, 0b00000, 0b00100, 0b00000],	// ?
+	[0b01100, 0b10010, 0b10110, 0b10110, 0b10000, 0b01100, 0b00000],	// @
+	[0b01100, 0b10010, 0b10010, 0b11110, 0b10010, 0b10010, 0b00000],	// A
+	[0b11100, 0b10010, 0b11100, 0b10010, 0b10010, 0b11100, 0b00000],	// B
+	[0b01100, 0b10010, 0b10000, 0b10000, 0b10010, 0b01100, 0b00000],	// C
+	[0b11100, 0b10010, 0b10010, 0b10010, 0b10010, 0b11100, 0b00000],	// D
+	[0b11110, 0b10000, 0b11100, 0b10000, 0b10000, 0b11110, 0b00000],	// E
+	[0b11110, 0b10000, 0b11100, 0b10000, 0b10000, 0b10000, 0b00000],	// F
+	[0b01100, 0b10010, 0b10000, 0b10110, 0b10010, 0b01110, 0b00000],	// G
+	[0b10010, 0b10010, 0b11110, 0b10010, 0b10010, 0b10010, 0b00000],	// H
+	[0b01110, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110, 0b00000],	// I
+	[0b00010, 0b00010, 0b00010, 0b00010, 0b10010, 0b01100, 0b00000],	// J
+	[0b10010, 0b10100, 0b11000, 0b11000, 0b10100, 0b10010, 0b00000],	// K
+	[0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b11110, 0b00000],	// L
+	[0b10010, 0b11110, 0b11110, 0b10010, 0b10010, 0b10010, 0b00000],	// M
+	[0b10010, 0b11010, 0b11010, 0b10110, 0b10110, 0b10010, 0b00000],	// N
+	[0b01100, 0b10010, 0b10010, 0b10010, 0b10010, 0b01100, 0b00000],	// O
+	[0b11100, 0b10010, 0b10010, 0b11100, 0b10000, 0b10000, 0b00000],	// P
+	[0b01100, 0b10010, 0b10010, 0b10010, 0b11010, 0b01100, 0b00010],	// Q
+	[0b11100, 0b10010, 0b10010, 0b11100, 0b10100, 0b10010, 0b00000],	// R
+	[0b01100, 0b10010, 0b01000, 0b00100, 0b10010, 0b01100, 0b00000],	// S
+	[0b01110, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00000],	// T
+	[0b10010, 0b10010, 0b10010, 0b10010, 0b10010, 0b01100, 0b00000],	// U
+	[0b10010, 0b10010, 0b10010, 0b10010, 0b01100, 0b01100, 0b00000],	// V
+	[0b10010, 0b10010, 0b10010, 0b11110, 0b11110, 0b10010, 0b00000],	// W
+	[0b10010, 0b10010, 0b01100, 0b01100, 0b10010, 0b10010, 0b00000],	// X
+	[0b01010, 0b01010, 0b01010, 0b00100, 0b00100, 0b00100, 0b00000],	// Y
+	[0b11110, 0b00010, 0b00100, 0b01000, 0b10000, 0b11110, 0b00000],	// Z
+	[0b01110, 0b01000, 0b01000, 0b01000, 0b01000, 0b01110, 0b00000],	// [
+	[0b00000, 0b10000, 0b01000, 0b00100, 0b00010, 0b00000, 0b00000],	// backslash
+	[0b01110, 0b00010, 0b00010, 0b00010, 0b00010, 0b01110, 0b00000],	// ]
+	[0b00100, 0b01010, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000],	// ^
+	[0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b11110, 0b00000],	// _
+	[0b01000, 0b00100, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000],	// `
+	[0b00000, 0b00000, 0b01110, 0b10010, 0b10110, 0b01010, 0b00000],	// a
+	[0b10000, 0b10000, 0b11100, 0b10010, 0b10010, 0b11100, 0b00000],	// b
+	[0b00000, 0b00000, 0b01100, 0b10000, 0b10000, 0b01100, 0b00000],	// c
+	[0b00010, 0b00010, 0b01110, 0b10010, 0b10010, 0b01110, 0b00000],	// d
+	[0b00000, 0b00000, 0b01100, 0b10110, 0b11000, 0b01100, 0b00000],	// e
+	[0b00100, 0b01010, 0b01000, 0b11100, 0b01000, 0b01000, 0b00000],	// f
+	[0b00000, 0b00000, 0b01110, 0b10010, 0b01100, 0b10000, 0b01110],	// g
+	[0b10000, 0b10000, 0b11100, 0b10010, 0b10010, 0b10010, 0b00000],	// h
+	[0b00100, 0b00000, 0b01100, 0b00100, 0b00100, 0b01110, 0b00000],	// i
+	[0b00010, 0b00000, 0b00010, 0b00010, 0b00010, 0b01010, 0b00100],	// j
+	[0b10000, 0b10000, 0b10100, 0b11000, 0b10100, 0b10010, 0b00000],	// k
+	[0b01100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110, 0b00000],	// l
+	[0b00000, 0b00000, 0b10100, 0b11110, 0b10010, 0b10010, 0b00000],	// m
+	[0b00000, 0b00000, 0b11100, 0b10010, 0b10010, 0b10010, 0b00000],	// n
+	[0b00000, 0b00000, 0b01100, 0b10010, 0b10010, 0b01100, 0b00000],	// o
+	[0b00000, 0b00000, 0b11100, 0b10010, 0b10010, 0b11100, 0b10000],	// p
+	[0b00000, 0b00000, 0b01110, 0b10010, 0b10010, 0b01110, 0b00010],	// q
+	[0b00000, 0b00000, 0b11100, 0b10010, 0b10000, 0b10000, 0b00000],	// r
+	[0b00000, 0b00000, 0b01110, 0b11000, 0b00110, 0b11100, 0b00000],	// s
+	[0b01000, 0b01000, 0b11100, 0b01000, 0b01000, 0b00110, 0b00000],	// t
+	[0b00000, 0b00000, 0b10010, 0b10010, 0b10010, 0b01110, 0b00000],	// u
+	[0b00000, 0b00000, 0b01010, 0b01010, 0b01010, 0b00100, 0b00000],	// v
+	[0b00000, 0b00000, 0b10010, 0b10010, 0b11110, 0b11110, 0b00000],	// w
+	[0b00000, 0b00000, 0b10010, 0b01100, 0b01100, 0b10010, 0b00000],	// x
+	[0b00000, 0b00000, 0b10010, 0b10010, 0b01010, 0b00100, 0b01000],	// y
+	[0b00000, 0b00000, 0b11110, 0b00100, 0b01000, 0b11110, 0b00000],	// z
+	[0b00010, 0b00100, 0b01100, 0b00100, 0b00100, 0b00010, 0b00000],	// {
+	[0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00000],	// |
+	[0b01000, 0b00100, 0b00110, 0b00100, 0b00100, 0b01000, 0b00000],	// }
+	[0b01010, 0b10100, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000],	// ~
+];
@@ -0,0 +1,61 @@
+use std::io;
+use std::io::Write;
+use utils::canvas::Canvas;
+use utils::pixel_format::PixelFormat;
+
+/// A software backend for presenting frames without a window: frames are drawn into memory and can be written to image files.
+/// Used for headless rendering, e.g. by tools, tests and benchmarks, or on machines without SDL.
+pub struct FrameBuffer {
+	/// The pixels of the frame as RGB triples, row by row
+	pixels: Vec<u8>,
+	width: usize,
+	height: usize
+}
+
+impl FrameBuffer {
+	/// The memory layout of the pixels, which matches the layout of PPM images
+	const PIXEL_FORMAT: PixelFormat = PixelFormat::RGB24;
+
+	/// Creates a black frame buffer of the given size. The frame buffer is at least one pixel wide and high.
+	pub fn new(width: usize, height: usize) -> FrameBuffer {
+		let mut frame_buffer = FrameBuffer {pixels: Vec::new(), width: 0, height: 0};
+		frame_buffer.resize(width, height);
+		frame_buffer
+	}
+
+	/// The width of the frame in pixels
+	pub fn width(&self) -> usize {
+		self.width
+	}
+
+	/// The height of the frame in pixels
+	pub fn height(&self) -> usize {
+		self.height
+	}
+
+	/// The pixels of the frame as RGB triples, row by row.
+	pub fn pixels(&self) -> &[u8] {
+		&self.pixels
+	}
+
+	/// Changes the size of the frame buffer. The content of the frame is cleared.
+	pub fn resize(&mut self, width: usize, height: usize) {
+		let (width, height) = (width.max(1), height.max(1));
+		self.pixels = vec![0; width * height * FrameBuffer::PIXEL_FORMAT.bytes_per_pixel()];
+		self.width = width;
+		self.height = height;
+	}
+
+	/// Creates a new canvas for drawing a single frame and passes it to the given block. Works like `View::draw_canvas`, except that
+	/// the frame is kept in memory.
+	pub fn draw_canvas<F, T>(&mut self, drawing_function: F) -> T where F: FnOnce(&mut Canvas) -> T {
+		let pitch = self.width * FrameBuffer::PIXEL_FORMAT.bytes_per_pixel();
+		drawing_function(&mut Canvas::new(&mut self.pixels, pitch, self.width, self.height, FrameBuffer::PIXEL_FORMAT))
+	}
+
+	/// Writes the frame as binary PPM image, which is understood by most image viewers and converters.
+	pub fn write_ppm<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+		write!(writer, "P6\n{} {}\n255\n", self.width, self.height)?;
+		writer.write_all(&self.pixels)
+	}
+}
@@ -0,0 +1,51 @@
+use utils::color::RGBColor;
+
+/// An image with an alpha channel that can be drawn onto a canvas.
+pub struct Image {
+	width: usize,
+	height: usize,
+
+	/// The colors of the pixels, row by row
+	pixels: Vec<RGBColor>,
+
+	/// The opacity of the pixels, row by row (0: transparent, 255: opaque)
+	alpha: Vec<u8>
+}
+
+impl Image {
+	/// Creates an image by determining the color and opacity of each pixel using the given function.
+	///
+	/// # Parameters:
+	///		- width, height:	The size of the image in pixels
+	///		- pixel:			Returns the color and the opacity (0: transparent, 255: opaque) for the pixel at the given coordinates
+	pub fn from_fn<F>(width: usize, height: usize, pixel: F) -> Image where F: Fn(usize, usize) -> (RGBColor, u8) {
+		let mut pixels = Vec::with_capacity(width * height);
+		let mut alpha = Vec::with_capacity(width * height);
+
+		for y in 0..height {
+			for x in 0..width {
+				let (color, opacity) = pixel(x, y);
+				pixels.push(color);
+				alpha.push(opacity);
+			}
+		}
+
+		Image {width, height, pixels, alpha}
+	}
+
+	/// The width of the image
+	pub fn width(&self) -> usize {
+		self.width
+	}
+
+	/// The height of the image
+	pub fn height(&self) -> usize {
+		self.height
+	}
+
+	/// The color and opacity of the pixel at the given coordinates.
+	pub fn pixel(&self, x: usize, y: usize) -> (&RGBColor, u8) {
+		let index = y * self.width + x;
+		(&self.pixels[index], self.alpha[index])
+	}
+}
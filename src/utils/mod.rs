@@ -1,2 +1,8 @@
+pub mod canvas;
 pub mod color;
-pub mod geometry;
\ No newline at end of file
+pub mod config;
+pub mod font;
+pub mod frame_buffer;
+pub mod geometry;
+pub mod image;
+pub mod pixel_format;
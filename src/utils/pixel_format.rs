@@ -0,0 +1,94 @@
+use utils::color::RGBColor;
+
+/// The memory layout of a pixel. The names follow SDL's pixel formats: 32-bit formats are packed into a native-endian `u32`, with
+/// the first named component in the most significant byte. Alpha components are always opaque.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PixelFormat {
+	/// Three bytes per pixel in the order red, green, blue
+	RGB24,
+
+	/// A packed `u32` with red in the most significant byte and alpha in the least significant byte
+	RGBA8888,
+
+	/// A packed `u32` with blue in the most significant byte and alpha in the least significant byte
+	BGRA8888,
+
+	/// A packed `u32` with alpha in the most significant byte and blue in the least significant byte
+	ARGB8888
+}
+
+impl PixelFormat {
+	/// The number of bytes used for a single pixel
+	#[inline]
+	pub fn bytes_per_pixel(&self) -> usize {
+		match *self {
+			PixelFormat::RGB24 => 3,
+			PixelFormat::RGBA8888 | PixelFormat::BGRA8888 | PixelFormat::ARGB8888 => 4
+		}
+	}
+
+	/// Converts a color to the bytes of a pixel. Only the first `bytes_per_pixel()` bytes are used.
+	#[inline]
+	pub fn encode(&self, color: &RGBColor) -> [u8; 4] {
+		let (red, green, blue) = (color.red as u32, color.green as u32, color.blue as u32);
+
+		match *self {
+			PixelFormat::RGB24 => [color.red, color.green, color.blue, 0],
+			PixelFormat::RGBA8888 => (red << 24 | green << 16 | blue << 8 | 0xFF).to_ne_bytes(),
+			PixelFormat::BGRA8888 => (blue << 24 | green << 16 | red << 8 | 0xFF).to_ne_bytes(),
+			PixelFormat::ARGB8888 => (0xFF << 24 | red << 16 | green << 8 | blue).to_ne_bytes()
+		}
+	}
+
+	/// Converts the bytes of a pixel to a color.
+	///
+	/// # Parameters:
+	///		- pixel:	The bytes of the pixel. Must contain at least `bytes_per_pixel()` bytes.
+	#[inline]
+	pub fn decode(&self, pixel: &[u8]) -> RGBColor {
+		let packed = || u32::from_ne_bytes([pixel[0], pixel[1], pixel[2], pixel[3]]);
+		let unpack = |value: u32, red_shift: u32, green_shift: u32, blue_shift: u32| {
+			RGBColor {red: (value >> red_shift) as u8, green: (value >> green_shift) as u8, blue: (value >> blue_shift) as u8}
+		};
+
+		match *self {
+			PixelFormat::RGB24 => RGBColor {red: pixel[0], green: pixel[1], blue: pixel[2]},
+			PixelFormat::RGBA8888 => unpack(packed(), 24, 16, 8),
+			PixelFormat::BGRA8888 => unpack(packed(), 8, 16, 24),
+			PixelFormat::ARGB8888 => unpack(packed(), 16, 8, 0)
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use proptest::prelude::*;
+
+	const FORMATS: [PixelFormat; 4] = [PixelFormat::RGB24, PixelFormat::RGBA8888, PixelFormat::BGRA8888, PixelFormat::ARGB8888];
+
+	proptest! {
+		#[test]
+		fn decode_inverts_encode(red in 0u8.., green in 0u8.., blue in 0u8..) {
+			let color = RGBColor {red, green, blue};
+			for format in FORMATS.iter() {
+				prop_assert_eq!(format.decode(&format.encode(&color)), color.clone());
+			}
+		}
+	}
+
+	#[test]
+	fn packed_formats_match_memory_layout() {
+		let color = RGBColor {red: 1, green: 2, blue: 3};
+
+		if cfg!(target_endian = "little") {
+			assert_eq!(PixelFormat::RGBA8888.encode(&color), [0xFF, 3, 2, 1]);
+			assert_eq!(PixelFormat::BGRA8888.encode(&color), [0xFF, 1, 2, 3]);
+			assert_eq!(PixelFormat::ARGB8888.encode(&color), [3, 2, 1, 0xFF]);
+		} else {
+			assert_eq!(PixelFormat::RGBA8888.encode(&color), [1, 2, 3, 0xFF]);
+			assert_eq!(PixelFormat::BGRA8888.encode(&color), [3, 2, 1, 0xFF]);
+			assert_eq!(PixelFormat::ARGB8888.encode(&color), [0xFF, 1, 2, 3]);
+		}
+	}
+}
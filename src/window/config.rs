@@ -0,0 +1,143 @@
+use std::fmt;
+use utils::config::Config;
+
+/// The settings used for opening a window.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WindowConfig {
+	/// The title of the window
+	pub title: String,
+
+	/// The initial width of the window in logical points. Also the width of the screen mode in exclusive fullscreen mode.
+	pub width: u32,
+
+	/// The initial height of the window in logical points. Also the height of the screen mode in exclusive fullscreen mode.
+	pub height: u32,
+
+	/// Whether the window is opened in fullscreen mode
+	pub fullscreen: Fullscreen,
+
+	/// Whether presenting frames is synchronized with the display's refresh rate
+	pub vsync: bool,
+
+	/// The index of the display the window is opened on
+	pub display: u32
+}
+
+/// The fullscreen modes of a window.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Fullscreen {
+	/// A normal, resizable window
+	Off,
+
+	/// A borderless window covering the entire display, keeping the display's screen mode
+	Borderless,
+
+	/// Switches the display to the screen mode closest to the window's size
+	Exclusive
+}
+
+impl WindowConfig {
+	/// The command line options overriding the keys of the configuration file (e.g. "--size 1024x768" overrides `window.size`).
+	pub const OPTIONS: [(&'static str, &'static str); 5] = [
+		("--size", "window.size"), ("--fullscreen", "window.fullscreen"), ("--vsync", "window.vsync"), ("--display", "window.display"),
+		("--title", "window.title")
+	];
+
+	/// Reads the window settings from a configuration. Keys that are not set keep their default values.
+	///
+	/// # Parameters:
+	///		- config:		The configuration containing the keys `window.size` (e.g. "1024x768"), `window.fullscreen` ("off", "borderless" or
+	///						"exclusive"), `window.vsync` ("on" or "off"), `window.display` (starting at 0) and `window.title`
+	pub fn from_config(config: &Config) -> Result<WindowConfig, String> {
+		let mut window_config = WindowConfig::default();
+
+		if let Some((width, height)) = config.convert("window.size", parse_size)? {
+			window_config.width = width;
+			window_config.height = height;
+		}
+
+		window_config.fullscreen = config.convert("window.fullscreen", Fullscreen::parse)?.unwrap_or(window_config.fullscreen);
+		window_config.vsync = config.convert("window.vsync", parse_switch)?.unwrap_or(window_config.vsync);
+		window_config.display = config.convert("window.display", |value| value.parse().ok())?.unwrap_or(window_config.display);
+		window_config.title = config.get("window.title").map_or(window_config.title, |title| title.to_string());
+
+		Ok(window_config)
+	}
+}
+
+impl Default for WindowConfig {
+	fn default() -> WindowConfig {
+		WindowConfig {title: "Ray Casting Demo".to_string(), width: 800, height: 600, fullscreen: Fullscreen::Off, vsync: true, display: 0}
+	}
+}
+
+impl Fullscreen {
+	/// Parses the name of a fullscreen mode, as written by `Display`.
+	pub fn parse(name: &str) -> Option<Fullscreen> {
+		match name {
+			"off" => Some(Fullscreen::Off),
+			"borderless" => Some(Fullscreen::Borderless),
+			"exclusive" => Some(Fullscreen::Exclusive),
+			_ => None
+		}
+	}
+
+	/// Returns the mode following this mode, for cycling through the modes.
+	pub fn next(&self) -> Fullscreen {
+		match *self {
+			Fullscreen::Off => Fullscreen::Borderless,
+			Fullscreen::Borderless => Fullscreen::Exclusive,
+			Fullscreen::Exclusive => Fullscreen::Off
+		}
+	}
+}
+
+impl fmt::Display for Fullscreen {
+	fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		match *self {
+			Fullscreen::Off => write!(formatter, "off"),
+			Fullscreen::Borderless => write!(formatter, "borderless"),
+			Fullscreen::Exclusive => write!(formatter, "exclusive")
+		}
+	}
+}
+
+/// Parses a size like "1024x768". Both values must be positive.
+fn parse_size(value: &str) -> Option<(u32, u32)> {
+	let mut parts = value.splitn(2, 'x').map(|part| part.trim().parse::<u32>());
+
+	match (parts.next(), parts.next()) {
+		(Some(Ok(width)), Some(Ok(height))) if width > 0 && height > 0 => Some((width, height)),
+		_ => None
+	}
+}
+
+/// Parses a switch like "on" or "off".
+pub fn parse_switch(value: &str) -> Option<bool> {
+	match value {
+		"on" | "true" | "yes" | "1" => Some(true),
+		"off" | "false" | "no" | "0" => Some(false),
+		_ => None
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn reads_window_settings() {
+		let config = Config::parse("window.size = 1024x768\nwindow.fullscreen = borderless\nwindow.vsync = off\nwindow.display = 1\nwindow.title = Maze").unwrap();
+		let expected = WindowConfig {title: "Maze".to_string(), width: 1024, height: 768, fullscreen: Fullscreen::Borderless, vsync: false, display: 1};
+
+		assert_eq!(WindowConfig::from_config(&config), Ok(expected));
+		assert_eq!(WindowConfig::from_config(&Config::new()), Ok(WindowConfig::default()));
+	}
+
+	#[test]
+	fn rejects_invalid_settings() {
+		for line in ["window.size = 1024", "window.size = 0x768", "window.fullscreen = maybe", "window.vsync = adaptive", "window.display = -1"].iter() {
+			assert!(WindowConfig::from_config(&Config::parse(line).unwrap()).is_err(), "{}", line);
+		}
+	}
+}
@@ -1,84 +1,110 @@
-extern crate sdl2;
+use std::fmt;
 
-use sdl2::event::Event as SDLEvent;
-use sdl2::event::WindowEvent as SDLWindowEvent;
-use sdl2::keyboard::Keycode as SDLKeycode;
+/// Possible events emitted by the source. Mouse positions are given in logical points relative to the window's top left corner.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Event {
+	/// The application should be terminated.
+	Quit,
 
-/// Wrapper around SDL to provide events
-pub struct EventSource {
-	event_pump: sdl2::EventPump
-}
+	/// The window was resized.
+	Resize,
 
-impl EventSource {
-	/// Creates a new event source from a given SDL context.
-	pub fn new(sdl_context: &sdl2::Sdl) -> EventSource {
-		let event_pump = sdl_context.event_pump().unwrap();
-		EventSource {event_pump: event_pump}
-	}
+	/// A key was pressed down. Repeated key presses of a held key are not reported.
+	KeyDown(Keycode),
 
-	/// Polls the next event from the source.
-	pub fn poll_next_event(&mut self) -> Option<Event> {
-		if let Some(event) = self.event_pump.poll_iter().next() {
-			match event {
-				SDLEvent::Quit {..} | SDLEvent::KeyDown {keycode: Some(SDLKeycode::Escape), ..} => {
-					return Some(Event::Quit)
-				},
-				SDLEvent::Window {win_event, ..} => {
-					match win_event {
-						SDLWindowEvent::Resized(..) | SDLWindowEvent::SizeChanged(..) => return Some(Event::Resize),
-						_ => None
-					}					
-				}
-				_ => None
-			}
-		}
-		else {
-			None
-		}
-	}
+	/// A key was released.
+	KeyUp(Keycode),
 
-	/// Provides a set of key codes for the currently pressed keys.
-	pub fn pressed_keycodes(&self) -> Vec<Keycode> {
-		self.event_pump
-			.keyboard_state()
-			.pressed_scancodes()
-			.filter_map({|scancode| 
-				if let Some(sdl_keycode) = SDLKeycode::from_scancode(scancode) {
-					Keycode::from_sdl_keycode(sdl_keycode)
-				}
-				else {
-					None
-				}
-
-			})
-			.collect()
-	}
+	/// The window gained the keyboard focus.
+	FocusGained,
+
+	/// The window lost the keyboard focus.
+	FocusLost,
+
+	/// The window was minimized.
+	Minimized,
+
+	/// The window was restored from being minimized or maximized.
+	Restored,
+
+	/// The mouse was moved.
+	MouseMotion {x: i32, y: i32, relative_x: i32, relative_y: i32},
+
+	/// A mouse button was pressed down.
+	MouseButtonDown {button: MouseButton, x: i32, y: i32},
+
+	/// A mouse button was released.
+	MouseButtonUp {button: MouseButton, x: i32, y: i32},
+
+	/// The mouse wheel was scrolled. Positive values scroll to the right and away from the user.
+	MouseWheel {x: i32, y: i32},
+
+	/// Text was entered. May contain several characters, e.g. if entered by an input method.
+	TextInput(String),
+
+	/// A button of a game controller was pressed down.
+	ControllerButtonDown(ControllerButton)
 }
 
-/// Possible events emitted by the source.
-pub enum Event {
-	/// The application should be terminated.
-	Quit,
+/// Mouse buttons
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MouseButton {
+	Left, Middle, Right
+}
 
-	/// The window was resized.
-	Resize
+/// The buttons of a game controller, named after the layout of an XBox controller
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ControllerButton {
+	A, B, X, Y,
+	Back, Start,
+	DPadUp, DPadDown, DPadLeft, DPadRight
 }
 
 /// Keycodes to detect pressed keys
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Keycode {
-	Left, Right, Up, Down
+	Left, Right, Up, Down,
+	A, B, C, D, E, F, G, H, I, J, K, L, M,
+	N, O, P, Q, R, S, T, U, V, W, X, Y, Z,
+	F1, F2, F3, F4, F5, F6, F11,
+	PageUp, PageDown, Home, Tab, LeftBracket, RightBracket,
+	Space, Escape, Return, Backspace, Backquote
 }
 
 impl Keycode {
-	/// Converts an SDL Keycode to an abstraction layer keycode.
-	fn from_sdl_keycode(sdl_keycode: SDLKeycode) -> Option<Keycode> {
-		match sdl_keycode {
-			SDLKeycode::Left => Some(Keycode::Left),
-			SDLKeycode::Right => Some(Keycode::Right),
-			SDLKeycode::Up => Some(Keycode::Up),
-			SDLKeycode::Down => Some(Keycode::Down),
-			_ => None
+	/// All keycodes, e.g. for choosing a key binding
+	pub const ALL: [Keycode; 48] = [
+		Keycode::Left, Keycode::Right, Keycode::Up, Keycode::Down, Keycode::A, Keycode::B, Keycode::C, Keycode::D,
+		Keycode::E, Keycode::F, Keycode::G, Keycode::H, Keycode::I, Keycode::J, Keycode::K, Keycode::L,
+		Keycode::M, Keycode::N, Keycode::O, Keycode::P, Keycode::Q, Keycode::R, Keycode::S, Keycode::T,
+		Keycode::U, Keycode::V, Keycode::W, Keycode::X, Keycode::Y, Keycode::Z, Keycode::F1, Keycode::F2,
+		Keycode::F3, Keycode::F4, Keycode::F5, Keycode::F6, Keycode::F11, Keycode::PageUp, Keycode::PageDown, Keycode::Home,
+		Keycode::Tab, Keycode::LeftBracket, Keycode::RightBracket, Keycode::Space, Keycode::Escape, Keycode::Return,
+		Keycode::Backspace, Keycode::Backquote
+	];
+
+	/// Finds a keycode by its name (e.g. "PageUp"), as written by `Display`.
+	pub fn from_name(name: &str) -> Option<Keycode> {
+		Keycode::ALL.iter().find(|keycode| keycode.to_string() == name).cloned()
+	}
+
+}
+
+impl fmt::Display for Keycode {
+	fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		write!(formatter, "{:?}", self)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn finds_keycodes_by_name() {
+		for keycode in Keycode::ALL.iter() {
+			assert_eq!(Keycode::from_name(&keycode.to_string()), Some(*keycode));
 		}
+		assert_eq!(Keycode::from_name("Hyper"), None);
 	}
 }
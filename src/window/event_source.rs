@@ -0,0 +1,241 @@
+extern crate sdl2;
+
+use sdl2::GameControllerSubsystem;
+use sdl2::controller::Button as SDLControllerButton;
+use sdl2::controller::GameController;
+use sdl2::event::Event as SDLEvent;
+use sdl2::event::WindowEvent as SDLWindowEvent;
+use sdl2::keyboard::Keycode as SDLKeycode;
+use sdl2::mouse::MouseButton as SDLMouseButton;
+use sdl2::mouse::MouseUtil;
+use sdl2::mouse::MouseWheelDirection;
+use window::event::ControllerButton;
+use window::event::Event;
+use window::event::Keycode;
+use window::event::MouseButton;
+
+/// Wrapper around SDL to provide events
+pub struct EventSource {
+	event_pump: sdl2::EventPump,
+	mouse: MouseUtil,
+
+	/// Used for opening game controllers. Missing if SDL's game controller support is not available.
+	controller_subsystem: Option<GameControllerSubsystem>,
+
+	/// The connected game controllers. They only send events while they are open.
+	controllers: Vec<GameController>,
+
+	/// Whether the window has the keyboard focus
+	focused: bool,
+
+	/// Whether the window is minimized
+	minimized: bool
+}
+
+impl EventSource {
+	/// Creates a new event source from a given SDL context. Returns an error message if SDL's event handling is not available.
+	pub fn new(sdl_context: &sdl2::Sdl) -> Result<EventSource, String> {
+		let event_pump = sdl_context.event_pump().map_err(|error| format!("Could not initialize the event handling: {}", error))?;
+
+		// Game controllers are optional, the events of the keyboard and the mouse are still available without them
+		let controller_subsystem = sdl_context.game_controller()
+			.map_err(|error| eprintln!("Game controllers are not available: {}", error))
+			.ok();
+
+		Ok(EventSource {event_pump, mouse: sdl_context.mouse(), controller_subsystem, controllers: Vec::new(), focused: true, minimized: false})
+	}
+
+	/// Takes all pending events from the source. Should be called once per frame, so bursts of events (e.g. mouse motion) don't queue up.
+	pub fn poll_events(&mut self) -> Vec<Event> {
+		let sdl_events: Vec<SDLEvent> = self.event_pump.poll_iter().collect();
+		let mut events = Vec::new();
+
+		for sdl_event in sdl_events {
+			// SDL reports all controllers connected at startup or later
+			if let SDLEvent::ControllerDeviceAdded {which, ..} = sdl_event {
+				self.open_controller(which);
+			}
+
+			if let Some(event) = Event::from_sdl_event(sdl_event) {
+				match event {
+					Event::FocusGained => { self.focused = true; },
+					Event::FocusLost => { self.focused = false; },
+					Event::Minimized => { self.minimized = true; },
+					Event::Restored => { self.minimized = false; },
+					_ => {}
+				}
+				events.push(event);
+			}
+		}
+
+		events
+	}
+
+	/// Captures the mouse: the cursor is hidden and mouse motion is reported even at the borders of the window (e.g. for looking around).
+	/// Releasing the mouse shows the cursor again (e.g. for choosing items of a menu).
+	pub fn set_mouse_captured(&mut self, captured: bool) {
+		if self.mouse.relative_mouse_mode() != captured {
+			self.mouse.set_relative_mouse_mode(captured);
+		}
+	}
+
+	/// Opens a game controller, so it sends events.
+	///
+	/// # Parameters:
+	///		- joystick_index:	SDL's index of the controller
+	fn open_controller(&mut self, joystick_index: u32) {
+		if let Some(ref controller_subsystem) = self.controller_subsystem {
+			match controller_subsystem.open(joystick_index) {
+				Ok(controller) => self.controllers.push(controller),
+				Err(error) => eprintln!("Could not open game controller {}: {}", joystick_index, error)
+			}
+		}
+	}
+
+	/// Whether the window has the keyboard focus, according to the events polled so far
+	pub fn has_focus(&self) -> bool {
+		self.focused
+	}
+
+	/// Whether the window is minimized, according to the events polled so far
+	pub fn is_minimized(&self) -> bool {
+		self.minimized
+	}
+
+	/// Provides a set of key codes for the currently pressed keys.
+	pub fn pressed_keycodes(&self) -> Vec<Keycode> {
+		self.event_pump
+			.keyboard_state()
+			.pressed_scancodes()
+			.filter_map({|scancode| 
+				if let Some(sdl_keycode) = SDLKeycode::from_scancode(scancode) {
+					Keycode::from_sdl_keycode(sdl_keycode)
+				}
+				else {
+					None
+				}
+
+			})
+			.collect()
+	}
+}
+
+impl Event {
+	/// Converts an SDL event to an abstraction layer event. Returns `None` for events that are not handled.
+	fn from_sdl_event(sdl_event: SDLEvent) -> Option<Event> {
+		match sdl_event {
+			SDLEvent::Quit {..} => Some(Event::Quit),
+			SDLEvent::KeyDown {keycode: Some(sdl_keycode), repeat: false, ..} => Keycode::from_sdl_keycode(sdl_keycode).map(Event::KeyDown),
+			SDLEvent::KeyUp {keycode: Some(sdl_keycode), ..} => Keycode::from_sdl_keycode(sdl_keycode).map(Event::KeyUp),
+			SDLEvent::Window {win_event, ..} => {
+				match win_event {
+					SDLWindowEvent::Resized(..) | SDLWindowEvent::SizeChanged(..) => Some(Event::Resize),
+					SDLWindowEvent::FocusGained => Some(Event::FocusGained),
+					SDLWindowEvent::FocusLost => Some(Event::FocusLost),
+					SDLWindowEvent::Minimized => Some(Event::Minimized),
+					SDLWindowEvent::Restored => Some(Event::Restored),
+					_ => None
+				}
+			},
+			SDLEvent::MouseMotion {x, y, xrel, yrel, ..} => Some(Event::MouseMotion {x, y, relative_x: xrel, relative_y: yrel}),
+			SDLEvent::MouseButtonDown {mouse_btn, x, y, ..} => MouseButton::from_sdl_mouse_button(mouse_btn).map(|button| Event::MouseButtonDown {button, x, y}),
+			SDLEvent::MouseButtonUp {mouse_btn, x, y, ..} => MouseButton::from_sdl_mouse_button(mouse_btn).map(|button| Event::MouseButtonUp {button, x, y}),
+			SDLEvent::MouseWheel {x, y, direction, ..} => {
+				// Some systems report flipped wheel movements for "natural" scrolling
+				let sign = if direction == MouseWheelDirection::Flipped { -1 } else { 1 };
+				Some(Event::MouseWheel {x: x * sign, y: y * sign})
+			},
+			SDLEvent::TextInput {text, ..} => Some(Event::TextInput(text)),
+			SDLEvent::ControllerButtonDown {button, ..} => ControllerButton::from_sdl_controller_button(button).map(Event::ControllerButtonDown),
+			_ => None
+		}
+	}
+}
+
+impl MouseButton {
+	/// Converts an SDL mouse button to an abstraction layer mouse button.
+	fn from_sdl_mouse_button(sdl_mouse_button: SDLMouseButton) -> Option<MouseButton> {
+		match sdl_mouse_button {
+			SDLMouseButton::Left => Some(MouseButton::Left),
+			SDLMouseButton::Middle => Some(MouseButton::Middle),
+			SDLMouseButton::Right => Some(MouseButton::Right),
+			_ => None
+		}
+	}
+}
+
+impl ControllerButton {
+	/// Converts an SDL controller button to an abstraction layer controller button.
+	fn from_sdl_controller_button(sdl_button: SDLControllerButton) -> Option<ControllerButton> {
+		match sdl_button {
+			SDLControllerButton::A => Some(ControllerButton::A),
+			SDLControllerButton::B => Some(ControllerButton::B),
+			SDLControllerButton::X => Some(ControllerButton::X),
+			SDLControllerButton::Y => Some(ControllerButton::Y),
+			SDLControllerButton::Back => Some(ControllerButton::Back),
+			SDLControllerButton::Start => Some(ControllerButton::Start),
+			SDLControllerButton::DPadUp => Some(ControllerButton::DPadUp),
+			SDLControllerButton::DPadDown => Some(ControllerButton::DPadDown),
+			SDLControllerButton::DPadLeft => Some(ControllerButton::DPadLeft),
+			SDLControllerButton::DPadRight => Some(ControllerButton::DPadRight),
+			_ => None
+		}
+	}
+}
+
+impl Keycode {
+	/// Converts an SDL Keycode to an abstraction layer keycode.
+	fn from_sdl_keycode(sdl_keycode: SDLKeycode) -> Option<Keycode> {
+		match sdl_keycode {
+			SDLKeycode::Left => Some(Keycode::Left),
+			SDLKeycode::Right => Some(Keycode::Right),
+			SDLKeycode::Up => Some(Keycode::Up),
+			SDLKeycode::Down => Some(Keycode::Down),
+			SDLKeycode::A => Some(Keycode::A),
+			SDLKeycode::B => Some(Keycode::B),
+			SDLKeycode::C => Some(Keycode::C),
+			SDLKeycode::D => Some(Keycode::D),
+			SDLKeycode::E => Some(Keycode::E),
+			SDLKeycode::F => Some(Keycode::F),
+			SDLKeycode::G => Some(Keycode::G),
+			SDLKeycode::H => Some(Keycode::H),
+			SDLKeycode::I => Some(Keycode::I),
+			SDLKeycode::J => Some(Keycode::J),
+			SDLKeycode::K => Some(Keycode::K),
+			SDLKeycode::L => Some(Keycode::L),
+			SDLKeycode::M => Some(Keycode::M),
+			SDLKeycode::N => Some(Keycode::N),
+			SDLKeycode::O => Some(Keycode::O),
+			SDLKeycode::P => Some(Keycode::P),
+			SDLKeycode::Q => Some(Keycode::Q),
+			SDLKeycode::R => Some(Keycode::R),
+			SDLKeycode::S => Some(Keycode::S),
+			SDLKeycode::T => Some(Keycode::T),
+			SDLKeycode::U => Some(Keycode::U),
+			SDLKeycode::V => Some(Keycode::V),
+			SDLKeycode::W => Some(Keycode::W),
+			SDLKeycode::X => Some(Keycode::X),
+			SDLKeycode::Y => Some(Keycode::Y),
+			SDLKeycode::Z => Some(Keycode::Z),
+			SDLKeycode::F1 => Some(Keycode::F1),
+			SDLKeycode::F2 => Some(Keycode::F2),
+			SDLKeycode::F3 => Some(Keycode::F3),
+			SDLKeycode::F4 => Some(Keycode::F4),
+			SDLKeycode::F5 => Some(Keycode::F5),
+			SDLKeycode::F6 => Some(Keycode::F6),
+			SDLKeycode::F11 => Some(Keycode::F11),
+			SDLKeycode::PageUp => Some(Keycode::PageUp),
+			SDLKeycode::PageDown => Some(Keycode::PageDown),
+			SDLKeycode::Home => Some(Keycode::Home),
+			SDLKeycode::Tab => Some(Keycode::Tab),
+			SDLKeycode::LeftBracket => Some(Keycode::LeftBracket),
+			SDLKeycode::RightBracket => Some(Keycode::RightBracket),
+			SDLKeycode::Space => Some(Keycode::Space),
+			SDLKeycode::Escape => Some(Keycode::Escape),
+			SDLKeycode::Return | SDLKeycode::KpEnter => Some(Keycode::Return),
+			SDLKeycode::Backspace => Some(Keycode::Backspace),
+			SDLKeycode::Backquote => Some(Keycode::Backquote),
+			_ => None
+		}
+	}
+}
@@ -1,13 +1,22 @@
-extern crate sdl2;
-
+pub mod config;
 pub mod event;
+#[cfg(feature = "sdl")]
+pub mod event_source;
+pub mod render_scale;
+#[cfg(feature = "sdl")]
 pub mod view;
-pub mod canvas;
 
+use window::config::Fullscreen;
+#[cfg(feature = "sdl")]
+use window::config::WindowConfig;
+#[cfg(feature = "sdl")]
+use window::event_source::EventSource;
+use window::render_scale::RenderScale;
+#[cfg(feature = "sdl")]
 use window::view::View;
-use window::event::EventSource;
 
 /// A window that can be used for drawing and that provides user events. May be a window or a HTML canvas.
+#[cfg(feature = "sdl")]
 pub struct Window {
 	/// The view that can be used for drawing
 	pub view: View,
@@ -16,14 +25,32 @@ pub struct Window {
 	pub event_source: EventSource
 }
 
+/// The settings of a view that can be changed while the application is running, e.g. in the menu. Settings and menus only depend on
+/// this trait, so they work without SDL.
+pub trait ViewSettings {
+	/// Determines the size of the internal frame buffer and how it is scaled to the window
+	fn render_scale(&self) -> &RenderScale;
+
+	/// Changes the size of the internal frame buffer and how it is scaled to the window. Frames rendered at a lower resolution are scaled
+	/// up to the window.
+	fn set_render_scale(&mut self, render_scale: RenderScale);
+
+	/// The current fullscreen mode of the window
+	fn fullscreen(&self) -> Fullscreen;
+
+	/// Changes the fullscreen mode of the window. Returns an error message if the mode is not supported, the window keeps its mode then.
+	fn set_fullscreen(&mut self, fullscreen: Fullscreen) -> Result<(), String>;
+}
+
+#[cfg(feature = "sdl")]
 impl Window {
-	/// Creates a new window.
-	pub fn new() -> Window {
-		let sdl_context = sdl2::init().unwrap();
-	    
-	    let view = View::new(&sdl_context);
-	    let event_source = EventSource::new(&sdl_context);
-
-		Window {view, event_source}
+	/// Creates a new window. Returns an error message if the window can't be opened with the given settings.
+	pub fn new(config: &WindowConfig) -> Result<Window, String> {
+		let sdl_context = sdl2::init().map_err(|error| format!("Could not initialize SDL: {}", error))?;
+
+		let view = View::new(&sdl_context, config)?;
+		let event_source = EventSource::new(&sdl_context)?;
+
+		Ok(Window {view, event_source})
 	}
 }
@@ -0,0 +1,163 @@
+use std::fmt;
+
+/// Determines the size of the internal frame buffer and how it is scaled up to the window.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RenderScale {
+	/// The resolution of the internal frame buffer
+	pub resolution: Resolution,
+
+	/// How the frame buffer is scaled up to the window
+	pub upscaling: Upscaling,
+
+	/// How pixels are interpolated while scaling
+	pub filtering: Filtering
+}
+
+/// The resolution of the internal frame buffer.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Resolution {
+	/// The frame buffer has a size relative to the window's size (1.0: native resolution)
+	Relative(f64),
+
+	/// The frame buffer has a fixed width and height (e.g. 320x200)
+	Fixed(u32, u32)
+}
+
+/// Describes how a frame buffer with a fixed resolution is scaled up to the window.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Upscaling {
+	/// Scales by the largest integer factor fitting into the window, so all pixels have the same size
+	Integer,
+
+	/// Scales by the largest factor fitting into the window
+	Fractional
+}
+
+/// Describes how pixels are interpolated while scaling.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Filtering {
+	/// Keeps hard pixel edges for a pixelated look
+	Nearest,
+
+	/// Interpolates between pixels for a smooth look
+	Linear
+}
+
+impl RenderScale {
+	/// Renders at the window's native resolution.
+	pub fn native() -> RenderScale {
+		RenderScale {resolution: Resolution::Relative(1.0), upscaling: Upscaling::Fractional, filtering: Filtering::Linear}
+	}
+
+	/// Renders a pixelated image at 320x200 pixels.
+	pub fn retro() -> RenderScale {
+		RenderScale {resolution: Resolution::Fixed(320, 200), upscaling: Upscaling::Integer, filtering: Filtering::Nearest}
+	}
+}
+
+impl Resolution {
+	/// The resolutions that can be cycled through at runtime.
+	const PRESETS: [Resolution; 5] = [
+		Resolution::Relative(1.0), Resolution::Relative(0.5), Resolution::Relative(0.25), Resolution::Fixed(640, 400), Resolution::Fixed(320, 200)
+	];
+
+	/// Returns the preset following this resolution.
+	pub fn next_preset(&self) -> Resolution {
+		let index = Resolution::PRESETS.iter().position(|preset| preset == self).map_or(0, |index| index + 1);
+		Resolution::PRESETS[index % Resolution::PRESETS.len()].clone()
+	}
+
+	/// Returns the preset preceding this resolution.
+	pub fn previous_preset(&self) -> Resolution {
+		let count = Resolution::PRESETS.len();
+		let index = Resolution::PRESETS.iter().position(|preset| preset == self).map_or(0, |index| index + count - 1);
+		Resolution::PRESETS[index % count].clone()
+	}
+
+	/// Parses a resolution as written by `Display` (e.g. "50 %" or "640x400").
+	pub fn parse(value: &str) -> Option<Resolution> {
+		if value.ends_with('%') {
+			let percentage: f64 = value.trim_end_matches('%').trim().parse().ok()?;
+			return if percentage > 0.0 && percentage <= 100.0 { Some(Resolution::Relative(percentage / 100.0)) } else { None };
+		}
+
+		let mut parts = value.splitn(2, 'x').map(|part| part.trim().parse::<u32>());
+		match (parts.next(), parts.next()) {
+			(Some(Ok(width)), Some(Ok(height))) if width > 0 && height > 0 => Some(Resolution::Fixed(width, height)),
+			_ => None
+		}
+	}
+
+}
+
+impl fmt::Display for Resolution {
+	fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		match *self {
+			Resolution::Relative(scale) => write!(formatter, "{:.0} %", scale * 100.0),
+			Resolution::Fixed(width, height) => write!(formatter, "{}x{}", width, height)
+		}
+	}
+}
+
+impl Upscaling {
+	/// Parses the name of an upscaling mode, as written by `Display`.
+	pub fn parse(name: &str) -> Option<Upscaling> {
+		match name {
+			"integer" => Some(Upscaling::Integer),
+			"fractional" => Some(Upscaling::Fractional),
+			_ => None
+		}
+	}
+
+}
+
+impl fmt::Display for Upscaling {
+	fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		match *self {
+			Upscaling::Integer => write!(formatter, "integer"),
+			Upscaling::Fractional => write!(formatter, "fractional")
+		}
+	}
+}
+
+impl Filtering {
+	/// Parses the name of a filtering mode, as written by `Display`.
+	pub fn parse(name: &str) -> Option<Filtering> {
+		match name {
+			"nearest" => Some(Filtering::Nearest),
+			"linear" => Some(Filtering::Linear),
+			_ => None
+		}
+	}
+
+}
+
+impl fmt::Display for Filtering {
+	fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		match *self {
+			Filtering::Nearest => write!(formatter, "nearest"),
+			Filtering::Linear => write!(formatter, "linear")
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parses_displayed_resolutions() {
+		for resolution in Resolution::PRESETS.iter() {
+			assert_eq!(Resolution::parse(&resolution.to_string()).as_ref(), Some(resolution));
+		}
+		assert_eq!(Resolution::parse("0 %"), None);
+		assert_eq!(Resolution::parse("640x"), None);
+	}
+
+	#[test]
+	fn cycles_through_presets_in_both_directions() {
+		for resolution in Resolution::PRESETS.iter() {
+			assert_eq!(&resolution.next_preset().previous_preset(), resolution);
+		}
+	}
+}
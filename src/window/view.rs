@@ -1,39 +1,341 @@
 extern crate sdl2;
 
-use window::canvas::Canvas;
+use sdl2::pixels::Color;
+use sdl2::pixels::PixelFormatEnum;
+use sdl2::rect::Rect;
+use sdl2::render::Texture;
+use sdl2::render::TextureCreator;
+use sdl2::render::WindowCanvas;
+use sdl2::video::FullscreenType;
+use sdl2::video::WindowContext;
+use std::fmt;
+use utils::canvas::Canvas;
+use utils::pixel_format::PixelFormat;
+use window::config::Fullscreen;
+use window::config::WindowConfig;
+use window::render_scale::Filtering;
+use window::render_scale::RenderScale;
+use window::render_scale::Resolution;
+use window::render_scale::Upscaling;
+use window::ViewSettings;
 
 /// A screen tile that can be used for drawing. (E.g. the content tile of a window or a HTML canvas.)
 pub struct View {
-	canvas: sdl2::render::Canvas<sdl2::video::Window>
+	canvas: sdl2::render::Canvas<sdl2::video::Window>,
+	texture_creator: TextureCreator<WindowContext>,
+
+	/// The streaming texture frames are drawn to. It is kept across frames and only recreated if the frame size changes.
+	texture: Option<Texture>,
+
+	/// Determines the size of the internal frame buffer and how it is scaled to the window
+	render_scale: RenderScale,
+
+	/// The render scale of the last presented frame. It is restored if no frame buffer can be created for a new render scale.
+	presented_render_scale: RenderScale,
+
+	/// The fullscreen mode used when toggling fullscreen mode at runtime
+	preferred_fullscreen: Fullscreen,
+
+	/// The kind of renderer presenting the frames
+	backend: Backend
+}
+
+/// The kinds of renderers that can present frames.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Backend {
+	/// A hardware accelerated renderer using OpenGL
+	Accelerated,
+
+	/// SDL's software renderer, which works without a graphics driver
+	Software
+}
+
+impl Resolution {
+	/// Determines the size of the frame buffer for a window of the given size.
+	fn frame_size(&self, window_width: u32, window_height: u32) -> (u32, u32) {
+		match *self {
+			Resolution::Relative(scale) => {
+				let scale = scale.clamp(0.01, 1.0);
+				(((window_width as f64 * scale) as u32).max(1), ((window_height as f64 * scale) as u32).max(1))
+			},
+			Resolution::Fixed(width, height) => (width.max(1), height.max(1))
+		}
+	}
+}
+
+impl Upscaling {
+	/// Determines the area of the window a frame is drawn to. Frames are centered and keep their aspect ratio.
+	fn target_rect(&self, frame_size: (u32, u32), output_size: (u32, u32)) -> Rect {
+		let fitting_scale = (output_size.0 as f64 / frame_size.0 as f64).min(output_size.1 as f64 / frame_size.1 as f64);
+		let scale = match *self {
+			Upscaling::Integer => fitting_scale.floor().max(1.0),
+			Upscaling::Fractional => fitting_scale
+		};
+
+		let width = (frame_size.0 as f64 * scale) as u32;
+		let height = (frame_size.1 as f64 * scale) as u32;
+		let x = (output_size.0 as i32 - width as i32) / 2;
+		let y = (output_size.1 as i32 - height as i32) / 2;
+
+		Rect::new(x, y, width, height)
+	}
+}
+
+impl Filtering {
+	/// The value of SDL's scale quality hint
+	fn sdl_hint(&self) -> &'static str {
+		match *self {
+			Filtering::Nearest => "nearest",
+			Filtering::Linear => "linear"
+		}
+	}
+}
+
+impl fmt::Display for Backend {
+	fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		match *self {
+			Backend::Accelerated => write!(formatter, "accelerated"),
+			Backend::Software => write!(formatter, "software")
+		}
+	}
 }
 
 impl View {
-	/// Creates a new view using an SDL context.
-	pub fn new(sdl_context: &sdl2::Sdl) -> View {
-    	let video_subsystem = sdl_context.video().unwrap();
-    	let window = video_subsystem.window("Ray Casting Demo", 800, 600)
-			.resizable().position_centered().allow_highdpi().opengl().build().unwrap();
-    	let canvas = window.into_canvas()
-        	.accelerated().present_vsync().build().unwrap();			
-		
-		View {canvas}
+	/// The pixel format of the frame buffer. ARGB8888 is the native format of most renderers, so SDL does not need to convert frames.
+	const PIXEL_FORMAT: PixelFormat = PixelFormat::ARGB8888;
+
+	/// Creates a new view using an SDL context. Returns an error message if the window can't be opened with the given settings.
+	pub fn new(sdl_context: &sdl2::Sdl, config: &WindowConfig) -> Result<View, String> {
+		let video_subsystem = sdl_context.video().map_err(|error| format!("Could not initialize the video subsystem: {}", error))?;
+
+		// Center the window on the requested display
+		let display_count = video_subsystem.num_video_displays().map_err(|error| format!("Could not query the displays: {}", error))?;
+		if config.display as i32 >= display_count {
+			return Err(format!("Display {} does not exist, there are {} display(s) starting at index 0", config.display, display_count));
+		}
+		let bounds = video_subsystem.display_bounds(config.display as i32)
+			.map_err(|error| format!("Could not query the bounds of display {}: {}", config.display, error))?;
+		let x = bounds.x() + (bounds.width() as i32 - config.width as i32) / 2;
+		let y = bounds.y() + (bounds.height() as i32 - config.height as i32) / 2;
+
+		// Fall back to SDL's software renderer if there is no working OpenGL driver or hardware acceleration
+		let (canvas, backend) = match View::create_canvas(&video_subsystem, config, (x, y), Backend::Accelerated) {
+			Ok(canvas) => (canvas, Backend::Accelerated),
+			Err(error) => {
+				eprintln!("{}, falling back to the software renderer", error);
+				let canvas = View::create_canvas(&video_subsystem, config, (x, y), Backend::Software)?;
+				(canvas, Backend::Software)
+			}
+		};
+		let texture_creator = canvas.texture_creator();
+
+		let preferred_fullscreen = if config.fullscreen == Fullscreen::Off { Fullscreen::Borderless } else { config.fullscreen };
+
+		Ok(View {canvas, texture_creator, texture: None, render_scale: RenderScale::native(), presented_render_scale: RenderScale::native(), preferred_fullscreen, backend})
+	}
+
+	/// Opens a window and creates a renderer for it.
+	///
+	/// # Parameters:
+	///		- video_subsystem:	The SDL video subsystem used for opening the window
+	///		- config:			The window settings
+	///		- position:			The position of the window's top left corner
+	///		- backend:			Whether a hardware accelerated renderer using OpenGL or the software renderer is created
+	fn create_canvas(video_subsystem: &sdl2::VideoSubsystem, config: &WindowConfig, (x, y): (i32, i32), backend: Backend) -> Result<WindowCanvas, String> {
+		let mut window_builder = video_subsystem.window(&config.title, config.width, config.height);
+		window_builder.resizable().position(x, y).allow_highdpi();
+		if backend == Backend::Accelerated {
+			window_builder.opengl();
+		}
+		match config.fullscreen {
+			Fullscreen::Off => {},
+			Fullscreen::Borderless => { window_builder.fullscreen_desktop(); },
+			Fullscreen::Exclusive => { window_builder.fullscreen(); }
+		}
+		let window = window_builder.build()
+			.map_err(|error| format!("Could not open a {}x{} window (fullscreen {}, {} renderer): {}", config.width, config.height, config.fullscreen, backend, error))?;
+
+		let mut canvas_builder = match backend {
+			Backend::Accelerated => window.into_canvas().accelerated(),
+			Backend::Software => window.into_canvas().software()
+		};
+		if config.vsync {
+			canvas_builder = canvas_builder.present_vsync();
+		}
+		canvas_builder.build().map_err(|error| format!("Could not create the {} renderer (vsync {}): {}", backend, if config.vsync { "on" } else { "off" }, error))
+	}
+
+	/// The kind of renderer presenting the frames
+	pub fn backend(&self) -> Backend {
+		self.backend
+	}
+
+	/// Describes the renderer presenting the frames, e.g. "opengl (accelerated)"
+	pub fn backend_description(&self) -> String {
+		format!("{} ({})", self.canvas.info().name, self.backend)
+	}
+
+	/// Switches between a normal window and the fullscreen mode the window was configured with (borderless by default). Returns the new
+	/// fullscreen mode or an error message if it is not supported.
+	pub fn toggle_fullscreen(&mut self) -> Result<Fullscreen, String> {
+		let fullscreen = if self.fullscreen() == Fullscreen::Off { self.preferred_fullscreen } else { Fullscreen::Off };
+		self.set_fullscreen(fullscreen).map(|_| fullscreen)
+	}
+
+	/// Must be called whenever the window was resized, so the frame buffer is adjusted to the new window size.
+	pub fn resize(&mut self) {
+		self.invalidate_texture();
+	}
+
+	/// The number of physical pixels per logical point of the window (e.g. 2 on high-DPI displays). May change if the window is moved
+	/// to another display.
+	pub fn scale_factor(&self) -> f64 {
+		let (window_width, _) = self.canvas.window().size();
+		let (output_width, _) = self.output_size();
+		output_width as f64 / window_width.max(1) as f64
+	}
+
+	/// The number of frame buffer pixels per logical point of the window. Smaller than `scale_factor()` if frames are rendered at a
+	/// lower resolution and scaled up.
+	pub fn frame_scale_factor(&self) -> f64 {
+		let output_size = self.output_size();
+		let frame_size = self.render_scale.resolution.frame_size(output_size.0, output_size.1);
+		let target = self.render_scale.upscaling.target_rect(frame_size, output_size);
+
+		self.scale_factor() * frame_size.0 as f64 / target.width().max(1) as f64
+	}
+
+	/// The size of the frames drawn by `draw_canvas` for the current window size and render scale
+	pub fn frame_size(&self) -> (u32, u32) {
+		let output_size = self.output_size();
+		self.render_scale.resolution.frame_size(output_size.0, output_size.1)
+	}
+
+	/// Converts a position within the window (in logical points, e.g. of the mouse) to a position within the frame buffer.
+	pub fn frame_position(&self, x: i32, y: i32) -> (isize, isize) {
+		let output_size = self.output_size();
+		let frame_size = self.render_scale.resolution.frame_size(output_size.0, output_size.1);
+		let target = self.render_scale.upscaling.target_rect(frame_size, output_size);
+		let scale_factor = self.scale_factor();
+
+		let frame_x = (x as f64 * scale_factor - target.x() as f64) * frame_size.0 as f64 / target.width().max(1) as f64;
+		let frame_y = (y as f64 * scale_factor - target.y() as f64) * frame_size.1 as f64 / target.height().max(1) as f64;
+		(frame_x.floor() as isize, frame_y.floor() as isize)
 	}
 
 	/// Creates a new canvas for draing a single frame and passes it to the given block. After the block has been finished, the canvas is drawn to the
-	pub fn draw_canvas<F>(&mut self, drawing_function: F) where F: FnOnce(&mut Canvas), {
-		let texture_creator = self.canvas.texture_creator();
-		let width = self.canvas.window().size().0;
-		let height = self.canvas.window().size().1;
+	/// window. Returns an error message if the frame could not be drawn. If no frame buffer can be created for the current render scale (e.g. because
+	/// it is too large for the renderer), the render scale of the last presented frame is restored, so the next frame can be drawn again.
+	pub fn draw_canvas<F>(&mut self, drawing_function: F) -> Result<(), String> where F: FnOnce(&mut Canvas), {
+		// The drawable size changes without a resize event if the window is moved to a display with another DPI
+		let output_size = self.output_size();
+		let frame_size = self.render_scale.resolution.frame_size(output_size.0, output_size.1);
+		if self.texture.as_ref().is_some_and(|texture| (texture.query().width, texture.query().height) != frame_size) {
+			self.invalidate_texture();
+		}
 
-		let mut texture = texture_creator
-			.create_texture_streaming(sdl2::pixels::PixelFormatEnum::RGB24, width, height)
-			.unwrap();
+		let texture = match self.texture.take() {
+			Some(texture) => texture,
+			None => self.create_texture(frame_size).map_err(|error| self.restore_presented_render_scale(error))?
+		};
+		let texture = self.texture.insert(texture);
+		let query = texture.query();
+		let width = query.width as usize;
+		let height = query.height as usize;
 
 		texture.with_lock(None, |buffer: &mut [u8], pitch: usize| {
-			drawing_function(&mut Canvas::new(buffer, pitch, width as usize, height as usize));
-		}).unwrap();
+			drawing_function(&mut Canvas::new(buffer, pitch, width, height, View::PIXEL_FORMAT));
+		}).map_err(|error| format!("Could not draw the frame: {}", error))?;
+
+		// Clear the borders of frames not filling the entire window
+		let target = self.render_scale.upscaling.target_rect((query.width, query.height), output_size);
 
-		self.canvas.copy(&texture, None, None).unwrap();
+		self.canvas.set_draw_color(Color::RGB(0, 0, 0));
+		self.canvas.clear();
+		self.canvas.copy(texture, None, target).map_err(|error| format!("Could not present the frame: {}", error))?;
 		self.canvas.present();
+
+		self.presented_render_scale = self.render_scale.clone();
+		Ok(())
+	}
+
+	/// Restores the render scale of the last presented frame after no frame buffer could be created for the current render scale. Returns
+	/// the error message extended by the restored resolution.
+	fn restore_presented_render_scale(&mut self, error: String) -> String {
+		if self.render_scale == self.presented_render_scale {
+			return error;
+		}
+
+		self.render_scale = self.presented_render_scale.clone();
+		format!("{}, restored the resolution {}", error, self.render_scale.resolution)
+	}
+
+	/// The size of the window's drawable area in physical pixels. On high-DPI displays, this is larger than the window's logical size.
+	fn output_size(&self) -> (u32, u32) {
+		self.canvas.output_size().unwrap_or_else(|_| self.canvas.window().drawable_size())
+	}
+
+	/// Creates a streaming texture of the given size using the current render scale. Returns an error message if the renderer doesn't support the size.
+	fn create_texture(&self, (width, height): (u32, u32)) -> Result<Texture, String> {
+		// The scale quality is read by SDL whenever a texture is created.
+		sdl2::hint::set("SDL_RENDER_SCALE_QUALITY", self.render_scale.filtering.sdl_hint());
+
+		self.texture_creator
+			.create_texture_streaming(View::sdl_pixel_format(View::PIXEL_FORMAT), width, height)
+			.map_err(|error| format!("Could not create a frame buffer of {}x{} pixels: {}", width, height, error))
+	}
+
+	/// Converts a pixel format to the matching SDL pixel format.
+	fn sdl_pixel_format(format: PixelFormat) -> PixelFormatEnum {
+		match format {
+			PixelFormat::RGB24 => PixelFormatEnum::RGB24,
+			PixelFormat::RGBA8888 => PixelFormatEnum::RGBA8888,
+			PixelFormat::BGRA8888 => PixelFormatEnum::BGRA8888,
+			PixelFormat::ARGB8888 => PixelFormatEnum::ARGB8888
+		}
+	}
+
+	/// Releases the current texture, so a new one will be created for the next frame.
+	fn invalidate_texture(&mut self) {
+		if let Some(texture) = self.texture.take() {
+			// The texture's renderer is owned by this view, so it is still alive.
+			unsafe { texture.destroy(); }
+		}
+	}
 }
+
+impl ViewSettings for View {
+	fn render_scale(&self) -> &RenderScale {
+		&self.render_scale
+	}
+
+	fn set_render_scale(&mut self, render_scale: RenderScale) {
+		self.render_scale = render_scale;
+		self.invalidate_texture();
+	}
+
+	fn fullscreen(&self) -> Fullscreen {
+		match self.canvas.window().fullscreen_state() {
+			FullscreenType::Off => Fullscreen::Off,
+			FullscreenType::Desktop => Fullscreen::Borderless,
+			FullscreenType::True => Fullscreen::Exclusive
+		}
+	}
+
+	fn set_fullscreen(&mut self, fullscreen: Fullscreen) -> Result<(), String> {
+		let fullscreen_type = match fullscreen {
+			Fullscreen::Off => FullscreenType::Off,
+			Fullscreen::Borderless => FullscreenType::Desktop,
+			Fullscreen::Exclusive => FullscreenType::True
+		};
+
+		self.canvas.window_mut().set_fullscreen(fullscreen_type)
+			.map_err(|error| format!("Could not switch fullscreen mode to {}: {}", fullscreen, error))?;
+
+		if fullscreen != Fullscreen::Off {
+			self.preferred_fullscreen = fullscreen;
+		}
+		self.invalidate_texture();
+		Ok(())
+	}
 }
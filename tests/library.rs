@@ -0,0 +1,43 @@
+extern crate raycaster;
+
+use raycaster::game::Game;
+use raycaster::game::map::Map;
+use raycaster::game::player::Player;
+use raycaster::renderer::Renderer;
+use raycaster::renderer::profiler::Profiler;
+use raycaster::utils::canvas::Canvas;
+use raycaster::utils::frame_buffer::FrameBuffer;
+use raycaster::utils::geometry::Point;
+use raycaster::utils::pixel_format::PixelFormat;
+
+#[test]
+fn renders_into_pixel_buffer() {
+	let game = Game::new(Map::new("RRRR\nR  B\nRRRR"), Player {position: Point {x: 1.5, y: 1.5}, direction: 0.0});
+	let renderer = Renderer::new(game, 67.0, 100.0, 0.25, Profiler::new(false));
+
+	let (width, height) = (64, 40);
+	let mut buffer = vec![0u8; width * height * 3];
+	let mut canvas = Canvas::new(&mut buffer, width * 3, width, height, PixelFormat::RGB24);
+	renderer.render(&mut canvas);
+
+	// The player looks at the blue wall, which fills the center of the frame
+	let center = canvas.pixel(width / 2, height / 2);
+	assert!(center.blue > 0 && center.red == 0, "center pixel: {:?}", center);
+}
+
+#[test]
+fn frame_buffer_writes_ppm() {
+	let game = Game::new(Map::new("RRRR\nR  B\nRRRR"), Player {position: Point {x: 1.5, y: 1.5}, direction: 0.0});
+	let renderer = Renderer::new(game, 67.0, 100.0, 0.25, Profiler::new(false));
+
+	let mut frame_buffer = FrameBuffer::new(64, 40);
+	frame_buffer.draw_canvas(|canvas| renderer.render(canvas));
+
+	let mut image = Vec::new();
+	frame_buffer.write_ppm(&mut image).unwrap();
+
+	let header = b"P6\n64 40\n255\n";
+	assert_eq!(&image[..header.len()], &header[..]);
+	assert_eq!(&image[header.len()..], frame_buffer.pixels());
+	assert!(frame_buffer.pixels().iter().any(|&component| component != 0));
+}